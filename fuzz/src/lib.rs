@@ -0,0 +1,15 @@
+use adventoffrog::registry;
+use adventoffrog::runner::Parts;
+
+/// Feeds `data` through the parser and the parts of a puzzle, both in one go and separately.
+///
+/// Errors are expected for most inputs, only panics count as findings.
+pub fn run(year: u32, day: u8, data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let entry = registry::find(year, day).expect("Fuzz target for an unregistered puzzle");
+    for parts in [Parts::Both, Parts::One, Parts::Two] {
+        let _ = (entry.solver)(input, parts);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::runner::print_table;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, Ordering::Relaxed};
+
+/// The counters of the global [`CountingAllocator`].
+static COUNTERS: Counters = Counters::new();
+
+/// Allocation counters, only counting once enabled.
+pub struct Counters {
+    enabled: AtomicBool,
+    allocations: AtomicU64,
+    allocated_bytes: AtomicU64,
+    live_bytes: AtomicIsize,
+    peak_bytes: AtomicIsize,
+}
+
+impl Counters {
+    pub const fn new() -> Self {
+        Counters {
+            enabled: AtomicBool::new(false),
+            allocations: AtomicU64::new(0),
+            allocated_bytes: AtomicU64::new(0),
+            live_bytes: AtomicIsize::new(0),
+            peak_bytes: AtomicIsize::new(0),
+        }
+    }
+
+    pub fn enable(&self) {
+        self.enabled.store(true, Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Relaxed)
+    }
+
+    fn count(&self, allocated: usize, freed: usize) {
+        if !self.enabled.load(Relaxed) {
+            return;
+        }
+        if allocated > 0 {
+            self.allocations.fetch_add(1, Relaxed);
+            self.allocated_bytes.fetch_add(allocated as u64, Relaxed);
+        }
+        let delta = allocated as isize - freed as isize;
+        let live = self.live_bytes.fetch_add(delta, Relaxed) + delta;
+        self.peak_bytes.fetch_max(live, Relaxed);
+    }
+
+    /// Runs `f` and returns the allocations it made, or `None` if counting isn't enabled.
+    ///
+    /// Must not be nested.
+    pub fn measure<R>(&self, f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+        if !self.is_enabled() {
+            return (f(), None);
+        }
+
+        let allocations = self.allocations.load(Relaxed);
+        let bytes = self.allocated_bytes.load(Relaxed);
+        let live = self.live_bytes.load(Relaxed);
+        self.peak_bytes.store(live, Relaxed);
+
+        let result = f();
+
+        let stats = AllocStats {
+            allocations: self.allocations.load(Relaxed) - allocations,
+            bytes: self.allocated_bytes.load(Relaxed) - bytes,
+            peak_bytes: (self.peak_bytes.load(Relaxed) - live).max(0) as u64,
+        };
+        (result, Some(stats))
+    }
+}
+
+impl Default for Counters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A global allocator forwarding to [`System`] that counts allocations once [`enable`]d.
+///
+/// The counters are global, so allocations of other threads are included as well.
+pub struct CountingAllocator {
+    counters: &'static Counters,
+}
+
+impl CountingAllocator {
+    /// An allocator counting into the global counters used by [`measure`].
+    pub const fn new() -> Self {
+        CountingAllocator {
+            counters: &COUNTERS,
+        }
+    }
+}
+
+impl Default for CountingAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.counters.count(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.counters.count(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.counters.count(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            // Counted like allocating the new block and freeing the old one
+            self.counters.count(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Starts counting. Only has an effect if [`CountingAllocator`] is the global allocator.
+pub fn enable() {
+    COUNTERS.enable();
+}
+
+pub fn is_enabled() -> bool {
+    COUNTERS.is_enabled()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations, including reallocations.
+    pub allocations: u64,
+    /// Total number of bytes requested by all allocations.
+    pub bytes: u64,
+    /// Highest number of bytes allocated at the same time, on top of what was allocated before.
+    pub peak_bytes: u64,
+}
+
+/// Runs `f` and returns the allocations it made through the global allocator, or `None` if
+/// counting isn't enabled.
+///
+/// Must not be nested.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<AllocStats>) {
+    COUNTERS.measure(f)
+}
+
+/// Prints the allocations of each phase, e.g. `Parse` or `Part 1`.
+pub fn print(phases: &[(&'static str, AllocStats)]) {
+    const HEADER: [&str; 4] = ["Phase", "Allocations", "Bytes", "Peak bytes"];
+
+    let rows: Vec<_> = phases
+        .iter()
+        .map(|(phase, stats)| {
+            [
+                phase.to_string(),
+                stats.allocations.to_string(),
+                stats.bytes.to_string(),
+                stats.peak_bytes.to_string(),
+            ]
+        })
+        .collect();
+    print_table(HEADER, &rows, None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counters, CountingAllocator};
+    use std::alloc::{GlobalAlloc, Layout};
+
+    #[test]
+    fn test_counting() {
+        // The test binary uses the system allocator, so drive a counting one with counters of
+        // its own by hand. Nothing else touches them, so even the peak is exact.
+        static COUNTERS: Counters = Counters::new();
+        let allocator = CountingAllocator {
+            counters: &COUNTERS,
+        };
+        COUNTERS.enable();
+
+        let ((), stats) = COUNTERS.measure(|| unsafe {
+            let small = Layout::from_size_align(16, 8).unwrap();
+            let a = allocator.alloc(small);
+            let b = allocator.alloc(Layout::from_size_align(100, 8).unwrap());
+            let a = allocator.realloc(a, small, 64);
+            allocator.dealloc(b, Layout::from_size_align(100, 8).unwrap());
+            let c = allocator.alloc(small);
+            allocator.dealloc(a, Layout::from_size_align(64, 8).unwrap());
+            allocator.dealloc(c, small);
+        });
+        let stats = stats.unwrap();
+
+        assert_eq!(stats.allocations, 4);
+        assert_eq!(stats.bytes, 16 + 100 + 64 + 16);
+        assert_eq!(stats.peak_bytes, 164);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::runner::{Outcome, Parts, Report};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Accepted answers of a single year, stored as `<answers_path>/<year>.toml`.
+///
+/// ```toml
+/// [05]
+/// part1 = "35"
+/// part2 = "46"
+/// ```
+pub struct AnswerStore {
+    path: PathBuf,
+    days: BTreeMap<String, DayAnswers>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+impl DayAnswers {
+    fn part(&self, part: u8) -> Option<&String> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn part_mut(&mut self, part: u8) -> &mut Option<String> {
+        match part {
+            1 => &mut self.part1,
+            2 => &mut self.part2,
+            _ => panic!("Invalid part {part}"),
+        }
+    }
+}
+
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {expected})"),
+            Verdict::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+impl AnswerStore {
+    /// Loads the answers of `year`. A missing file is treated as an empty store.
+    pub fn load(answers_path: &Path, year: u32) -> Result<Self> {
+        let path = answers_path.join(format!("{year}.toml"));
+        let days = match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content)
+                .with_context(|| format!("Failed to parse answers: {path:?}"))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read answers: {path:?}")),
+        };
+
+        Ok(AnswerStore { path, days })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir:?}"))?;
+        }
+        let content = toml::to_string(&self.days)?;
+        fs::write(&self.path, content).with_context(|| format!("Failed to write {:?}", self.path))
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.days.get(&day_key(day))?.part(part).map(String::as_str)
+    }
+
+    /// Stores `answer`, returning the previously recorded one if it differed.
+    pub fn set(&mut self, day: u8, part: u8, answer: &str) -> Option<String> {
+        let slot = self.days.entry(day_key(day)).or_default().part_mut(part);
+        match slot.replace(answer.to_string()) {
+            Some(old) if old != answer => Some(old),
+            _ => None,
+        }
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &Result<String>) -> Verdict {
+        match (self.get(day, part), answer) {
+            (None, _) => Verdict::Unknown,
+            (Some(expected), Ok(answer)) if expected == answer => Verdict::Pass,
+            (Some(expected), _) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+
+    /// The verdicts of the `parts` that were to be solved. If the day didn't solve at all, e.g.
+    /// because the input failed to parse, every part with a recorded answer fails.
+    pub fn verdicts(&self, day: u8, outcome: &Outcome, parts: Parts) -> Vec<(u8, Verdict)> {
+        match outcome {
+            Outcome::Solved(report) => report
+                .parts()
+                .map(|(part, part_report)| (part, self.check(day, part, &part_report.answer)))
+                .collect(),
+            _ => [1, 2]
+                .into_iter()
+                .filter(|&part| parts.includes(part))
+                .map(|part| {
+                    let verdict = match self.get(day, part) {
+                        Some(expected) => Verdict::Fail {
+                            expected: expected.to_string(),
+                        },
+                        None => Verdict::Unknown,
+                    };
+                    (part, verdict)
+                })
+                .collect(),
+        }
+    }
+
+    /// Compares the `parts` of `outcome` against the store and writes the verdicts to `out`.
+    ///
+    /// Returns the number of failed parts.
+    pub fn check_outcome(
+        &self,
+        day: u8,
+        outcome: &Outcome,
+        parts: Parts,
+        out: &mut dyn Write,
+    ) -> Result<usize> {
+        let mut failures = 0;
+        for (part, verdict) in self.verdicts(day, outcome, parts) {
+            if let Verdict::Fail { .. } = verdict {
+                failures += 1;
+            }
+            writeln!(out, "Day {day:0>2} part {part}: {verdict}")?;
+        }
+        Ok(failures)
+    }
+
+    /// Records all successfully computed parts of `report` and notes each change in `out`.
+    pub fn record_report(&mut self, day: u8, report: &Report, out: &mut dyn Write) -> Result<()> {
+        for (part, part_report) in report.parts() {
+            let Ok(answer) = &part_report.answer else {
+                writeln!(out, "Day {day:0>2} part {part}: not recorded, no answer")?;
+                continue;
+            };
+            match self.set(day, part, answer) {
+                Some(old) => writeln!(
+                    out,
+                    "Day {day:0>2} part {part}: recorded {answer} (was {old})"
+                )?,
+                None => writeln!(out, "Day {day:0>2} part {part}: recorded {answer}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn day_key(day: u8) -> String {
+    format!("{day:0>2}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnswerStore, Verdict};
+    use crate::runner::{Outcome, Parts};
+    use anyhow::anyhow;
+    use indoc::indoc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_round_trip() {
+        let content = indoc! {r#"
+            [05]
+            part1 = "35"
+            part2 = "46"
+
+            [07]
+            part1 = "6440"
+        "#};
+
+        let mut store = AnswerStore {
+            path: Default::default(),
+            days: toml::from_str(content).unwrap(),
+        };
+        assert_eq!(store.get(5, 2), Some("46"));
+        assert_eq!(store.get(7, 2), None);
+        assert_eq!(store.get(6, 1), None);
+
+        assert_eq!(store.set(7, 2, "5905"), None);
+        assert_eq!(store.set(5, 1, "35"), None);
+        assert_eq!(store.set(5, 1, "36").as_deref(), Some("35"));
+        assert_eq!(
+            toml::to_string(&store.days).unwrap(),
+            indoc! {r#"
+                [05]
+                part1 = "36"
+                part2 = "46"
+
+                [07]
+                part1 = "6440"
+                part2 = "5905"
+            "#}
+        );
+
+        assert!(matches!(
+            store.check(7, 1, &Ok("6440".into())),
+            Verdict::Pass
+        ));
+        assert!(matches!(
+            store.check(7, 1, &Ok("6441".into())),
+            Verdict::Fail { .. }
+        ));
+        assert!(matches!(
+            store.check(7, 1, &Err(anyhow!("x"))),
+            Verdict::Fail { .. }
+        ));
+        assert!(matches!(
+            store.check(8, 1, &Ok("1".into())),
+            Verdict::Unknown
+        ));
+    }
+
+    #[test]
+    fn test_unsolved_days_fail() {
+        let store = AnswerStore {
+            path: Default::default(),
+            days: toml::from_str("[07]\npart1 = \"6440\"\n").unwrap(),
+        };
+
+        for outcome in [
+            Outcome::Failed(anyhow!("Failed to parse hand")),
+            Outcome::Skipped("Input not found".to_string()),
+            Outcome::Panicked("index out of bounds".to_string()),
+            Outcome::TimedOut(Duration::from_secs(1)),
+        ] {
+            let mut out = Vec::new();
+            let failures = store.check_outcome(7, &outcome, Parts::Both, &mut out);
+            assert_eq!(failures.unwrap(), 1);
+            assert_eq!(
+                String::from_utf8(out).unwrap(),
+                "Day 07 part 1: FAIL (expected 6440)\nDay 07 part 2: UNKNOWN\n"
+            );
+
+            let mut out = Vec::new();
+            let failures = store.check_outcome(7, &outcome, Parts::Two, &mut out);
+            assert_eq!(failures.unwrap(), 0);
+            let failures = store.check_outcome(8, &outcome, Parts::Both, &mut out);
+            assert_eq!(failures.unwrap(), 0);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::parse_error;
+use crate::runner::{print_table, Parts};
+use crate::Puzzle;
+use anyhow::Result;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+pub struct BenchOptions {
+    /// Number of measured iterations per phase.
+    pub iterations: u32,
+    /// Number of unmeasured iterations per phase before measuring starts.
+    pub warmup: u32,
+    /// Time after which measuring a phase stops, even if not all iterations ran yet.
+    pub budget: Duration,
+}
+
+/// Signature shared by all monomorphized [`bench()`] instances, so they can be stored in tables.
+pub type Bencher = fn(&str, &BenchOptions, Parts) -> Result<BenchReport>;
+
+pub struct BenchReport {
+    /// The measured phases, named e.g. `Parse` or `Part 1`.
+    pub phases: Vec<(&'static str, Stats)>,
+}
+
+impl BenchReport {
+    pub fn print(&self) {
+        const HEADER: [&str; 7] = [
+            "Phase", "Samples", "Min", "Median", "Mean", "Std dev", "Outliers",
+        ];
+
+        let rows: Vec<_> = self
+            .phases
+            .iter()
+            .map(|(phase, stats)| {
+                [
+                    phase.to_string(),
+                    stats.samples.to_string(),
+                    format!("{:?}", stats.min),
+                    format!("{:?}", stats.median),
+                    format!("{:?}", stats.mean),
+                    format!("{:?}", stats.std_dev),
+                    format!(
+                        "{} mild, {} severe",
+                        stats.mild_outliers, stats.severe_outliers
+                    ),
+                ]
+            })
+            .collect();
+
+        print_table(HEADER, &rows, None);
+    }
+}
+
+/// Summary statistics over the samples of one benchmarked phase.
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub std_dev: Duration,
+    /// Samples outside the inner fences (1.5 IQR beyond the quartiles), but inside the outer ones.
+    pub mild_outliers: usize,
+    /// Samples outside the outer fences (3 IQR beyond the quartiles).
+    pub severe_outliers: usize,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(
+            !samples.is_empty(),
+            "Can't compute statistics without samples"
+        );
+        samples.sort_unstable();
+
+        let nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        let n = nanos.len() as f64;
+        let mean = nanos.iter().sum::<f64>() / n;
+        let variance = match nanos.len() {
+            1 => 0.0,
+            _ => nanos.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0),
+        };
+
+        let q1 = quantile(&nanos, 0.25);
+        let q3 = quantile(&nanos, 0.75);
+        let iqr = q3 - q1;
+        let fences = |k: f64| (q1 - k * iqr)..=(q3 + k * iqr);
+        let (inner, outer) = (fences(1.5), fences(3.0));
+
+        Stats {
+            samples: samples.len(),
+            min: samples[0],
+            median: Duration::from_nanos(quantile(&nanos, 0.5).round() as u64),
+            mean: Duration::from_nanos(mean.round() as u64),
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+            mild_outliers: nanos
+                .iter()
+                .filter(|x| !inner.contains(x) && outer.contains(x))
+                .count(),
+            severe_outliers: nanos.iter().filter(|x| !outer.contains(x)).count(),
+        }
+    }
+}
+
+/// Linearly interpolated quantile of already sorted values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+/// Runs `f` for the warmup iterations and then samples it until either the requested number of
+/// iterations is reached or the time budget is used up.
+fn measure<R>(options: &BenchOptions, mut f: impl FnMut() -> R) -> Stats {
+    for _ in 0..options.warmup {
+        black_box(f());
+    }
+
+    let mut samples = Vec::with_capacity(options.iterations as usize);
+    let budget_start = Instant::now();
+    for _ in 0..options.iterations.max(1) {
+        let start = Instant::now();
+        let result = black_box(f());
+        samples.push(start.elapsed());
+        drop(result);
+
+        if budget_start.elapsed() > options.budget {
+            break;
+        }
+    }
+
+    Stats::from_samples(samples)
+}
+
+pub fn bench<'a, T: Puzzle<'a>>(
+    input: &'a str,
+    options: &BenchOptions,
+    parts: Parts,
+) -> Result<BenchReport> {
+    // Make sure parsing works at all before measuring it, so errors don't get swallowed.
+    let day_xy = T::parse(input).map_err(|e| parse_error::locate(e, input))?;
+
+    let mut phases = vec![("Parse", measure(options, || T::parse(black_box(input))))];
+    if parts == Parts::Both && day_xy.solve_both().is_some() {
+        phases.push(("Both parts", measure(options, || day_xy.solve_both())));
+    } else {
+        if parts.includes(1) {
+            phases.push(("Part 1", measure(options, || day_xy.solve_problem_1())));
+        }
+        if parts.includes(2) {
+            phases.push(("Part 2", measure(options, || day_xy.solve_problem_2())));
+        }
+    }
+
+    Ok(BenchReport { phases })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stats;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stats() {
+        let samples = [10, 12, 11, 13, 12, 11, 10, 12, 30, 100]
+            .map(Duration::from_nanos)
+            .to_vec();
+        let stats = Stats::from_samples(samples);
+
+        assert_eq!(stats.samples, 10);
+        assert_eq!(stats.min, Duration::from_nanos(10));
+        assert_eq!(stats.median, Duration::from_nanos(12));
+        assert_eq!(stats.mean, Duration::from_nanos(22));
+        assert_eq!(stats.std_dev, Duration::from_nanos(28));
+        assert_eq!(stats.mild_outliers, 0);
+        assert_eq!(stats.severe_outliers, 2);
+    }
+
+    #[test]
+    fn test_stats_single_sample() {
+        let stats = Stats::from_samples(vec![Duration::from_micros(5)]);
+        assert_eq!(stats.median, Duration::from_micros(5));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+        assert_eq!(stats.severe_outliers, 0);
+    }
+}
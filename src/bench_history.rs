@@ -0,0 +1,339 @@
+use crate::bench::BenchReport;
+use crate::runner::print_table;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One benchmark invocation, stored as a line of JSON in the history file.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchRun {
+    /// Optional name to refer to this run as a baseline.
+    pub name: Option<String>,
+    /// Short hash of the checked out commit, with `-dirty` appended if there were local changes.
+    pub commit: Option<String>,
+    /// UTC time of the run, e.g. `2023-12-05T06:00:00Z`.
+    pub date: String,
+    /// `debug` or `release`.
+    pub profile: String,
+    pub results: Vec<PhaseResult>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct PhaseResult {
+    pub year: u32,
+    pub day: u8,
+    pub phase: String,
+    pub samples: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub std_dev_ns: u64,
+}
+
+impl BenchRun {
+    /// Starts a run for the current commit, date and build profile.
+    pub fn new(name: Option<String>) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        BenchRun {
+            name,
+            commit: git_commit(),
+            date: format_date(now.as_secs()),
+            profile: if cfg!(debug_assertions) {
+                "debug"
+            } else {
+                "release"
+            }
+            .to_string(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, year: u32, day: u8, report: &BenchReport) {
+        let nanos =
+            |duration: std::time::Duration| u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        for (phase, stats) in &report.phases {
+            self.results.push(PhaseResult {
+                year,
+                day,
+                phase: phase.to_string(),
+                samples: stats.samples,
+                min_ns: nanos(stats.min),
+                median_ns: nanos(stats.median),
+                mean_ns: nanos(stats.mean),
+                std_dev_ns: nanos(stats.std_dev),
+            });
+        }
+    }
+
+    fn find(&self, year: u32, day: u8, phase: &str) -> Option<&PhaseResult> {
+        self.results
+            .iter()
+            .find(|r| r.year == year && r.day == day && r.phase == phase)
+    }
+
+    fn describe(&self) -> String {
+        let name = self.name.as_ref().map(|name| format!("{name}, "));
+        format!(
+            "{}commit {}, {}, {} build",
+            name.unwrap_or_default(),
+            self.commit.as_deref().unwrap_or("unknown"),
+            self.date,
+            self.profile
+        )
+    }
+}
+
+fn git_commit() -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git").args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let commit = git(&["rev-parse", "--short", "HEAD"])?;
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"])?;
+    if dirty.is_empty() {
+        Some(commit)
+    } else {
+        Some(format!("{commit}-dirty"))
+    }
+}
+
+/// Formats a Unix timestamp as an ISO 8601 UTC date.
+fn format_date(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / 86400, timestamp % 86400);
+
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// All recorded benchmark runs, stored as JSON Lines so new runs only need to be appended.
+pub struct BenchHistory {
+    path: PathBuf,
+    runs: Vec<BenchRun>,
+}
+
+impl BenchHistory {
+    /// Loads the history. A missing file is treated as an empty history.
+    pub fn load(path: &Path) -> Result<Self> {
+        let runs = match fs::read_to_string(path) {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .enumerate()
+                .map(|(i, line)| {
+                    serde_json::from_str(line)
+                        .with_context(|| format!("Invalid run in {path:?}, line {}", i + 1))
+                })
+                .collect::<Result<_>>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {path:?}")),
+        };
+
+        Ok(BenchHistory {
+            path: path.to_path_buf(),
+            runs,
+        })
+    }
+
+    /// Finds the baseline to compare against: the last run if `baseline` is `None`, otherwise the
+    /// last run with that name or a commit starting with it.
+    pub fn baseline(&self, baseline: Option<&str>) -> Option<&BenchRun> {
+        self.runs.iter().rev().find(|run| match baseline {
+            None => true,
+            Some(baseline) => {
+                run.name.as_deref() == Some(baseline)
+                    || run
+                        .commit
+                        .as_ref()
+                        .is_some_and(|commit| commit.starts_with(baseline))
+            }
+        })
+    }
+
+    pub fn append(&mut self, run: BenchRun) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir:?}"))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {:?}", self.path))?;
+        writeln!(file, "{}", serde_json::to_string(&run)?)
+            .with_context(|| format!("Failed to write {:?}", self.path))?;
+
+        self.runs.push(run);
+        Ok(())
+    }
+}
+
+/// Relative change of the median of each phase of `current` against `baseline`.
+///
+/// Returns the current result with the baseline median and the change, both `None` for phases
+/// missing in the baseline.
+pub fn compare<'a>(
+    baseline: &BenchRun,
+    current: &'a BenchRun,
+) -> Vec<(&'a PhaseResult, Option<u64>, Option<f64>)> {
+    current
+        .results
+        .iter()
+        .map(|result| {
+            let old = baseline
+                .find(result.year, result.day, &result.phase)
+                .map(|old| old.median_ns);
+            let change = old
+                .filter(|&old| old > 0)
+                .map(|old| (result.median_ns as f64 - old as f64) / old as f64);
+            (result, old, change)
+        })
+        .collect()
+}
+
+/// Prints the comparison, marking changes of more than `threshold` (e.g. `0.05` for 5%).
+pub fn print_comparison(baseline: &BenchRun, current: &BenchRun, threshold: f64) {
+    const HEADER: [&str; 5] = ["Day", "Phase", "Baseline", "Current", "Change"];
+
+    let ns = |ns: u64| format!("{:?}", std::time::Duration::from_nanos(ns));
+    let rows: Vec<_> = compare(baseline, current)
+        .into_iter()
+        .map(|(result, old, change)| {
+            let change = match change {
+                None => "new".to_string(),
+                Some(change) => {
+                    let verdict = if change > threshold {
+                        " SLOWER"
+                    } else if change < -threshold {
+                        " FASTER"
+                    } else {
+                        ""
+                    };
+                    format!("{:+.1}%{verdict}", change * 100.0)
+                }
+            };
+            [
+                format!("{} {:0>2}", result.year, result.day),
+                result.phase.clone(),
+                old.map(ns).unwrap_or_default(),
+                ns(result.median_ns),
+                change,
+            ]
+        })
+        .collect();
+
+    println!("Comparing medians against {}", baseline.describe());
+    if baseline.profile != current.profile {
+        println!(
+            "Warning: the baseline used a {} build, this is a {} build",
+            baseline.profile, current.profile
+        );
+    }
+    print_table(HEADER, &rows, None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, format_date, BenchHistory, BenchRun, PhaseResult};
+
+    fn run(name: Option<&str>, commit: &str, medians: &[(&str, u64)]) -> BenchRun {
+        BenchRun {
+            name: name.map(str::to_string),
+            commit: Some(commit.to_string()),
+            date: "2023-12-05T06:00:00Z".to_string(),
+            profile: "release".to_string(),
+            results: medians
+                .iter()
+                .map(|&(phase, median_ns)| PhaseResult {
+                    year: 2023,
+                    day: 5,
+                    phase: phase.to_string(),
+                    samples: 10,
+                    min_ns: median_ns,
+                    median_ns,
+                    mean_ns: median_ns,
+                    std_dev_ns: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_date(1701756000), "2023-12-05T06:00:00Z");
+        assert_eq!(format_date(951782400 + 3661), "2000-02-29T01:01:01Z");
+    }
+
+    #[test]
+    fn test_history() {
+        let path = std::env::temp_dir().join(format!(
+            "adventoffrog-bench-history-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut history = BenchHistory::load(&path).unwrap();
+        assert!(history.baseline(None).is_none());
+        history
+            .append(run(Some("before"), "abc1234", &[("Parse", 100)]))
+            .unwrap();
+        history
+            .append(run(None, "def5678-dirty", &[("Parse", 90)]))
+            .unwrap();
+
+        let history = BenchHistory::load(&path).unwrap();
+        assert_eq!(history.runs.len(), 2);
+        assert_eq!(history.baseline(None), history.runs.last());
+        assert_eq!(history.baseline(Some("before")), history.runs.first());
+        assert_eq!(history.baseline(Some("def5")), history.runs.last());
+        assert_eq!(history.baseline(Some("after")), None);
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = run(None, "abc1234", &[("Parse", 100), ("Part 1", 200)]);
+        let current = run(
+            None,
+            "def5678",
+            &[("Parse", 150), ("Part 1", 180), ("Part 2", 5)],
+        );
+
+        let changes: Vec<_> = compare(&baseline, &current)
+            .into_iter()
+            .map(|(result, old, change)| (result.phase.as_str(), old, change))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("Parse", Some(100), Some(0.5)),
+                ("Part 1", Some(200), Some(-0.1)),
+                ("Part 2", None, None),
+            ]
+        );
+    }
+}
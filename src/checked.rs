@@ -0,0 +1,209 @@
+//! Arithmetic on answers that can detect overflow.
+//!
+//! Solvers compute their answers through these functions instead of the plain operators. With
+//! the `checked-arithmetic` feature, an overflow becomes an [`Overflow`] error naming the
+//! operation, which the runner extends with the puzzle it happened in. Without the feature they
+//! compile down to the plain operators, which panic in debug builds and wrap in release builds,
+//! and to an `as` cast for [`from_f64`], which saturates and turns NaN into 0.
+
+use anyhow::Result;
+use std::fmt::{self, Display};
+use std::ops::{Add, Mul, Shl};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Overflow {
+    operation: &'static str,
+    /// Year and day of the puzzle, once known.
+    puzzle: Option<(u32, u8)>,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.puzzle {
+            Some((year, day)) => write!(f, "Overflow in {} ({year} day {day:0>2})", self.operation),
+            None => write!(f, "Overflow in {}", self.operation),
+        }
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Names the puzzle in an [`Overflow`], if `error` is or wraps one.
+pub fn attribute(mut error: anyhow::Error, year: u32, day: u8) -> anyhow::Error {
+    if let Some(overflow) = error.downcast_mut::<Overflow>() {
+        overflow.puzzle = Some((year, day));
+    }
+    error
+}
+
+/// The unsigned integers answers are computed in.
+pub trait Integer:
+    Copy + Add<Output = Self> + Mul<Output = Self> + Shl<u32, Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_shl(self, rhs: u32) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn checked_shl(self, rhs: u32) -> Option<Self> {
+                    // The std version only checks the shift amount, not lost bits
+                    let shifted = <$t>::checked_shl(self, rhs)?;
+                    (shifted >> rhs == self).then_some(shifted)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u32, u64, u128, usize);
+
+#[cfg(feature = "checked-arithmetic")]
+fn check<T>(result: Option<T>, operation: &'static str) -> Result<T> {
+    result.ok_or_else(|| {
+        anyhow::Error::new(Overflow {
+            operation,
+            puzzle: None,
+        })
+    })
+}
+
+pub fn add<T: Integer>(a: T, b: T, operation: &'static str) -> Result<T> {
+    #[cfg(feature = "checked-arithmetic")]
+    return check(a.checked_add(b), operation);
+    #[cfg(not(feature = "checked-arithmetic"))]
+    {
+        let _ = operation;
+        Ok(a + b)
+    }
+}
+
+pub fn mul<T: Integer>(a: T, b: T, operation: &'static str) -> Result<T> {
+    #[cfg(feature = "checked-arithmetic")]
+    return check(a.checked_mul(b), operation);
+    #[cfg(not(feature = "checked-arithmetic"))]
+    {
+        let _ = operation;
+        Ok(a * b)
+    }
+}
+
+/// Shifts `a` left by `bits`, i.e. multiplies it with `2^bits`.
+pub fn shl<T: Integer>(a: T, bits: u32, operation: &'static str) -> Result<T> {
+    #[cfg(feature = "checked-arithmetic")]
+    return check(a.checked_shl(bits), operation);
+    #[cfg(not(feature = "checked-arithmetic"))]
+    {
+        let _ = operation;
+        Ok(a << bits)
+    }
+}
+
+pub fn sum<T: Integer>(values: impl IntoIterator<Item = T>, operation: &'static str) -> Result<T> {
+    values
+        .into_iter()
+        .try_fold(T::ZERO, |acc, value| add(acc, value, operation))
+}
+
+pub fn product<T: Integer>(
+    values: impl IntoIterator<Item = T>,
+    operation: &'static str,
+) -> Result<T> {
+    values
+        .into_iter()
+        .try_fold(T::ONE, |acc, value| mul(acc, value, operation))
+}
+
+/// Converts an integral `value` into a `u64`, which it has to be in range of.
+pub fn from_f64(value: f64, operation: &'static str) -> Result<u64> {
+    #[cfg(feature = "checked-arithmetic")]
+    {
+        // u64::MAX isn't representable, `as f64` rounds it up to 2^64
+        let in_range = (0.0..u64::MAX as f64).contains(&value);
+        check(in_range.then_some(value as u64), operation)
+    }
+    #[cfg(not(feature = "checked-arithmetic"))]
+    {
+        let _ = operation;
+        Ok(value as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add, attribute, from_f64, mul, product, shl, sum, Overflow};
+
+    #[test]
+    fn test_in_range() {
+        assert_eq!(add(2u32, 3, "test").unwrap(), 5);
+        assert_eq!(mul(2u64, 3, "test").unwrap(), 6);
+        assert_eq!(shl(1u64, 63, "test").unwrap(), 1 << 63);
+        assert_eq!(sum([1u32, 2, 3], "test").unwrap(), 6);
+        assert_eq!(product([2u32, 3, 4], "test").unwrap(), 24);
+        assert_eq!(product::<u32>([], "test").unwrap(), 1);
+        assert_eq!(from_f64(12.0, "test").unwrap(), 12);
+    }
+
+    #[cfg(feature = "checked-arithmetic")]
+    #[test]
+    fn test_overflow() {
+        let overflow = Overflow {
+            operation: "test",
+            puzzle: None,
+        };
+        let error = add(u32::MAX, 1, "test").unwrap_err();
+        assert_eq!(error.downcast::<Overflow>().unwrap(), overflow);
+        assert!(mul(u64::MAX / 2, 3, "test").is_err());
+        assert!(shl(3u64, 63, "test").is_err());
+        assert!(shl(1u32, 32, "test").is_err());
+        assert!(sum([u32::MAX, 1], "test").is_err());
+        assert!(from_f64(-1.0, "test").is_err());
+        assert!(from_f64(2f64.powi(64), "test").is_err());
+        assert!(from_f64(f64::NAN, "test").is_err());
+    }
+
+    #[cfg(feature = "checked-arithmetic")]
+    #[test]
+    fn test_solver_overflow() {
+        use crate::runner::{self, Parts};
+        use crate::year2023;
+
+        let input = "AAAAA 18446744073709551615\nKKKKK 1\n";
+        let report = runner::solve::<year2023::day07::Day>(input, Parts::One).unwrap();
+        let error = report.part_1.unwrap().answer.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Overflow in multiplying bid by rank (2023 day 07)"
+        );
+    }
+
+    #[test]
+    fn test_attribute() {
+        let overflow = Overflow {
+            operation: "summing bids",
+            puzzle: None,
+        };
+        let error = attribute(anyhow::Error::from(overflow).context("Part 1"), 2023, 7);
+        assert_eq!(
+            format!("{error:#}"),
+            "Part 1: Overflow in summing bids (2023 day 07)"
+        );
+    }
+}
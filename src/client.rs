@@ -0,0 +1,341 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Identifies this tool to the website, as requested by its operator.
+pub const USER_AGENT: &str = concat!(
+    "adventoffrog/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/LingMan/adventoffrog)"
+);
+
+/// Minimum time between two requests, so we don't put unnecessary load on the servers.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Environment variable holding the session token.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// Environment variable overriding [`DEFAULT_BASE_URL`], e.g. for a local mirror.
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+
+/// A client for the Advent of Code website, authenticated by the session cookie of a user.
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: Option<String>,
+    min_interval: Duration,
+    /// Stores the time of the last request, so the rate limit also holds across invocations.
+    last_request_file: PathBuf,
+}
+
+impl Client {
+    /// Creates a client. Without a session token only the rate limiting works, all requests fail.
+    pub fn new(base_url: &str, session: Option<String>, last_request_file: PathBuf) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .user_agent(USER_AGENT)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+
+        Client {
+            agent,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            min_interval: DEFAULT_MIN_INTERVAL,
+            last_request_file,
+        }
+    }
+
+    /// Creates a client for the configured website, keeping its state next to the inputs.
+    ///
+    /// The session is read as described in [`read_session`], falling back to
+    /// [`default_session_file`] if no `session_file` is given.
+    pub fn from_env(input_path: &Path, session_file: Option<&Path>) -> Result<Self> {
+        let session = match session_file {
+            Some(file) => read_session(Some(file))?,
+            None => read_session(default_session_file().as_deref())?,
+        };
+        let base_url = std::env::var(BASE_URL_ENV).unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        Ok(Client::new(
+            &base_url,
+            session,
+            input_path.join(".last_request"),
+        ))
+    }
+
+    pub fn with_min_interval(self, min_interval: Duration) -> Self {
+        Client {
+            min_interval,
+            ..self
+        }
+    }
+
+    /// Fetches `path`, e.g. `/2023/day/5/input`, and returns the response body.
+    pub fn get(&self, path: &str) -> Result<String> {
+        let url = format!("{}{path}", self.base_url);
+        let session = self.session()?;
+        self.wait_for_rate_limit()?;
+
+        let response = self
+            .agent
+            .get(&url)
+            .header("Cookie", format!("session={session}"))
+            .call();
+        Self::read_body(response).with_context(|| format!("Request to {url} failed"))
+    }
+
+    /// Posts `form` URL encoded to `path` and returns the response body.
+    pub fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        let url = format!("{}{path}", self.base_url);
+        let session = self.session()?;
+        self.wait_for_rate_limit()?;
+
+        let response = self
+            .agent
+            .post(&url)
+            .header("Cookie", format!("session={session}"))
+            .send_form(form.iter().copied());
+        Self::read_body(response).with_context(|| format!("Request to {url} failed"))
+    }
+
+    fn read_body(
+        response: Result<ureq::http::Response<ureq::Body>, ureq::Error>,
+    ) -> Result<String> {
+        response
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| match e {
+                ureq::Error::StatusCode(400) => {
+                    anyhow!("{e}, the session token is probably invalid or expired")
+                }
+                ureq::Error::StatusCode(404) => {
+                    anyhow!("{e}, the puzzle is probably not unlocked yet")
+                }
+                e => e.into(),
+            })
+    }
+
+    fn session(&self) -> Result<&str> {
+        self.session.as_deref().with_context(|| {
+            format!("No session token found. Set {SESSION_ENV} or store it in a session file.")
+        })
+    }
+
+    fn wait_for_rate_limit(&self) -> Result<()> {
+        let now = || {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+        };
+
+        let last_request = fs::read_to_string(&self.last_request_file)
+            .ok()
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(Duration::from_millis);
+        if let Some(wait) = last_request
+            .map(|last| (last + self.min_interval).saturating_sub(now()))
+            .filter(|wait| !wait.is_zero())
+        {
+            eprintln!("Waiting {wait:?} before sending the next request");
+            thread::sleep(wait);
+        }
+
+        if let Some(dir) = self.last_request_file.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir:?}"))?;
+        }
+        fs::write(&self.last_request_file, now().as_millis().to_string())
+            .with_context(|| format!("Failed to write {:?}", self.last_request_file))
+    }
+}
+
+/// Reads the session token from [`SESSION_ENV`] or, if that's unset, from `session_file`.
+///
+/// Returns `None` if neither provides a token.
+pub fn read_session(session_file: Option<&Path>) -> Result<Option<String>> {
+    if let Ok(session) = std::env::var(SESSION_ENV) {
+        return Ok(Some(session.trim().to_string()));
+    }
+
+    let Some(path) = session_file else {
+        return Ok(None);
+    };
+    match fs::read_to_string(path) {
+        Ok(session) => Ok(Some(session.trim().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read session file {path:?}")),
+    }
+}
+
+/// Default location of the session file, `~/.config/adventoffrog/session`.
+pub fn default_session_file() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".config/adventoffrog/session"))
+}
+
+/// Downloads the personal input of a day into `<input_path>/<year>/<day>.txt`.
+///
+/// Nothing is downloaded if the file already exists and isn't empty. Returns whether a download
+/// took place.
+pub fn fetch_input(client: &Client, input_path: &Path, year: u32, day: u8) -> Result<bool> {
+    let path = input_path
+        .join(year.to_string())
+        .join(format!("{day:0>2}.txt"));
+    if fs::metadata(&path).is_ok_and(|meta| meta.len() > 0) {
+        return Ok(false);
+    }
+
+    let input = client
+        .get(&format!("/{year}/day/{day}/input"))
+        .with_context(|| format!("Failed to download the input of {year} day {day}"))?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir:?}"))?;
+    }
+    fs::write(&path, input).with_context(|| format!("Failed to write {path:?}"))?;
+    Ok(true)
+}
+
+/// A minimal HTTP server standing in for the website in tests.
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    pub struct StubServer {
+        pub base_url: String,
+        handle: JoinHandle<Vec<String>>,
+    }
+
+    impl StubServer {
+        /// Answers one request per given `(status, body)` response, in order, then shuts down.
+        pub fn start(responses: Vec<(u16, &'static str)>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+            let handle = thread::spawn(move || {
+                let mut requests = Vec::new();
+                for (status, body) in responses {
+                    let (stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream);
+
+                    let mut request = String::new();
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if let Some((name, value)) = line.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                content_length = value.trim().parse().unwrap();
+                            }
+                        }
+                        request.push_str(&line);
+                        if line == "\r\n" || line.is_empty() {
+                            break;
+                        }
+                    }
+                    let mut body_in = vec![0; content_length];
+                    reader.read_exact(&mut body_in).unwrap();
+                    request.push_str(&String::from_utf8(body_in).unwrap());
+                    requests.push(request);
+
+                    let response = format!(
+                        "HTTP/1.1 {status} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    reader.get_mut().write_all(response.as_bytes()).unwrap();
+                }
+                requests
+            });
+
+            StubServer { base_url, handle }
+        }
+
+        /// Waits for all responses to be sent and returns the received requests.
+        pub fn requests(self) -> Vec<String> {
+            self.handle.join().unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::stub::StubServer;
+    use super::{fetch_input, Client};
+    use std::time::{Duration, Instant};
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("adventoffrog-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let dir = temp_dir("fetch");
+        let server = StubServer::start(vec![(200, "1 2 3\n")]);
+        let client = Client::new(
+            &server.base_url,
+            Some("abc".into()),
+            dir.join(".last_request"),
+        )
+        .with_min_interval(Duration::ZERO);
+
+        assert!(fetch_input(&client, &dir, 2023, 5).unwrap());
+        // Already cached, so this must not reach the server
+        assert!(!fetch_input(&client, &dir, 2023, 5).unwrap());
+        assert_eq!(
+            std::fs::read_to_string(dir.join("2023/05.txt")).unwrap(),
+            "1 2 3\n"
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2023/day/5/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\ncookie: session=abc\r\n"));
+        assert!(requests[0].contains(&format!("\r\nuser-agent: {}\r\n", super::USER_AGENT)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = temp_dir("errors");
+        let server = StubServer::start(vec![(404, "Not found")]);
+        let client = Client::new(
+            &server.base_url,
+            Some("abc".into()),
+            dir.join(".last_request"),
+        )
+        .with_min_interval(Duration::ZERO);
+        assert!(fetch_input(&client, &dir, 2023, 25).is_err());
+        assert!(!dir.join("2023/25.txt").exists());
+        server.requests();
+
+        // Without a session nothing gets sent at all
+        let client = Client::new("http://127.0.0.1:1", None, dir.join(".last_request"));
+        assert!(client.get("/").is_err());
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let dir = temp_dir("rate-limit");
+        let server = StubServer::start(vec![(200, "a"), (200, "b")]);
+        let min_interval = Duration::from_millis(200);
+
+        let start = Instant::now();
+        for expected in ["a", "b"] {
+            // A new client for each request, to make sure the limit holds across invocations
+            let client = Client::new(
+                &server.base_url,
+                Some("abc".into()),
+                dir.join(".last_request"),
+            )
+            .with_min_interval(min_interval);
+            assert_eq!(client.get("/").unwrap(), expected);
+        }
+        assert!(start.elapsed() >= min_interval);
+        server.requests();
+    }
+}
@@ -0,0 +1,151 @@
+use crate::output::Format;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the project-local config file, looked up in the working directory.
+pub const PROJECT_CONFIG: &str = "adventoffrog.toml";
+
+/// Defaults for the runner. Every setting is optional, command line flags take precedence.
+/// Relative paths are relative to the directory of the config file.
+///
+/// ```toml
+/// year = 2023
+/// input_path = "../aoc-inputs"
+/// format = "json"
+///
+/// [bench]
+/// warmup = 5
+/// ```
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub year: Option<u32>,
+    pub input_path: Option<PathBuf>,
+    pub answers_path: Option<PathBuf>,
+    pub format: Option<Format>,
+    pub session_file: Option<PathBuf>,
+    #[serde(default)]
+    pub bench: BenchConfig,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchConfig {
+    pub warmup: Option<u32>,
+    /// Maximum time in seconds spent benchmarking each phase.
+    pub time: Option<f64>,
+    pub history: Option<PathBuf>,
+}
+
+impl Config {
+    /// Makes the relative paths relative to `dir` instead of the working directory.
+    fn resolve_paths(&mut self, dir: &Path) {
+        let paths = [
+            &mut self.input_path,
+            &mut self.answers_path,
+            &mut self.session_file,
+            &mut self.bench.history,
+        ];
+        for path in paths.into_iter().flatten() {
+            *path = dir.join(&*path);
+        }
+    }
+}
+
+pub struct ConfigFile {
+    pub path: PathBuf,
+    pub config: Config,
+}
+
+/// Location of the user-level config file, `~/.config/adventoffrog/config.toml`.
+pub fn user_config_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".config/adventoffrog/config.toml"))
+}
+
+/// The locations searched for config files, in order of increasing precedence.
+pub fn search_paths() -> Vec<PathBuf> {
+    user_config_path()
+        .into_iter()
+        .chain([PathBuf::from(PROJECT_CONFIG)])
+        .collect()
+}
+
+/// Loads the existing config files among `paths`, skipping missing ones.
+pub fn load(paths: &[PathBuf]) -> Result<Vec<ConfigFile>> {
+    let mut files = Vec::new();
+    for path in paths {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read config {path:?}")),
+        };
+        let mut config: Config =
+            toml::from_str(&content).with_context(|| format!("Failed to parse config {path:?}"))?;
+        config.resolve_paths(path.parent().unwrap_or(Path::new("")));
+        files.push(ConfigFile {
+            path: path.clone(),
+            config,
+        });
+    }
+    Ok(files)
+}
+
+/// Looks up a setting, returning its value and the file it came from. Later files win.
+pub fn lookup<T>(files: &[ConfigFile], get: impl Fn(&Config) -> Option<T>) -> Option<(T, &Path)> {
+    files
+        .iter()
+        .rev()
+        .find_map(|file| Some((get(&file.config)?, file.path.as_path())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, lookup, Config};
+    use crate::output::Format;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_layers() {
+        let dir = std::env::temp_dir().join(format!("adventoffrog-config-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let (user, project) = (dir.join("user.toml"), dir.join("project.toml"));
+        fs::write(
+            &user,
+            "year = 2022\ninput_path = \"/aoc\"\nanswers_path = \"answers\"\n[bench]\nwarmup = 3\n",
+        )
+        .unwrap();
+        fs::write(&project, "year = 2023\nformat = \"csv\"\n").unwrap();
+
+        let files = load(&[user.clone(), dir.join("missing.toml"), project.clone()]).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(lookup(&files, |c| c.year), Some((2023, project.as_path())));
+        assert_eq!(
+            lookup(&files, |c| c.input_path.clone()),
+            Some((Path::new("/aoc").to_owned(), user.as_path()))
+        );
+        // Relative to the file's directory, not the working directory
+        assert_eq!(
+            lookup(&files, |c| c.answers_path.clone()),
+            Some((dir.join("answers"), user.as_path()))
+        );
+        assert_eq!(
+            lookup(&files, |c| c.bench.warmup),
+            Some((3, user.as_path()))
+        );
+        assert_eq!(
+            lookup(&files, |c| c.format),
+            Some((Format::Csv, project.as_path()))
+        );
+        assert_eq!(lookup(&files, |c| c.session_file.clone()), None);
+
+        fs::write(&project, "yaer = 2023\n").unwrap();
+        assert!(load(&[project]).is_err());
+        assert_eq!(toml::from_str::<Config>("").unwrap(), Config::default());
+    }
+}
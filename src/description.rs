@@ -0,0 +1,325 @@
+use crate::client::Client;
+use crate::examples::{self, Example};
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Downloads the description page of a day. Part two is only included once part one is solved.
+pub fn fetch_description(client: &Client, year: u32, day: u8) -> Result<String> {
+    client
+        .get(&format!("/{year}/day/{day}"))
+        .with_context(|| format!("Failed to download the description of {year} day {day}"))
+}
+
+/// Stores the description of a day as `<day>.md` and its examples as `<day>_example_<k>.txt`,
+/// with the answers found for them in the header, all in `<input_path>/<year>`.
+///
+/// Example files which already exist keep their input, only answers they don't declare yet are
+/// added to their header. Part two usually only becomes known after solving part one.
+pub fn save_description(input_path: &Path, year: u32, day: u8, html: &str) -> Result<()> {
+    let dir = input_path.join(year.to_string());
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {dir:?}"))?;
+
+    let articles = articles(html);
+    if articles.is_empty() {
+        bail!("Failed to find the puzzle description in the page");
+    }
+
+    let markdown = articles
+        .iter()
+        .map(|article| to_markdown(article))
+        .collect::<Vec<_>>()
+        .join("\n");
+    write(&dir.join(format!("{day:0>2}.md")), &markdown)?;
+
+    for example in extract_examples(html) {
+        println!(
+            "Example {}: part 1 {}, part 2 {}",
+            example.number,
+            example.part1.as_deref().unwrap_or("unknown"),
+            example.part2.as_deref().unwrap_or("unknown"),
+        );
+
+        let path = dir.join(examples::file_name(day, example.number));
+        if !fs::metadata(&path).is_ok_and(|meta| meta.len() > 0) {
+            write(&path, &example.to_file_content())?;
+            continue;
+        }
+
+        let mut existing = Example::load(&dir, day, example.number)?;
+        if existing.add_missing_answers(&example) {
+            write(&path, &existing.to_file_content())?;
+        } else {
+            println!("Skipped {path:?}, it already has all known answers");
+        }
+    }
+
+    Ok(())
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("Failed to write {path:?}"))?;
+    println!("Wrote {path:?}");
+    Ok(())
+}
+
+/// Returns the contents of the `<article>` elements, one per part.
+fn articles(html: &str) -> Vec<&str> {
+    html.split("<article")
+        .skip(1)
+        .filter_map(|rest| {
+            let (_, content) = rest.split_once('>')?;
+            Some(content.split_once("</article>")?.0)
+        })
+        .collect()
+}
+
+/// Finds the example of each part and the answer given for it.
+///
+/// Only the first `<pre><code>` block of a part is taken, later ones usually show intermediate
+/// steps. The answer is the last emphasised code, e.g. `<code><em>142</em></code>`, of the part.
+/// Part two reuses the example of part one if it doesn't come with its own.
+pub fn extract_examples(html: &str) -> Vec<Example> {
+    let mut examples: Vec<Example> = Vec::new();
+    for (part, article) in articles(html).into_iter().take(2).enumerate() {
+        let answer = last_emphasised_code(article);
+        let input = code_blocks(article).into_iter().next();
+
+        let example = match input {
+            Some(input) if examples.iter().all(|e| e.input != input) => {
+                examples.push(Example {
+                    number: examples.len() as u32 + 1,
+                    part1: None,
+                    part2: None,
+                    input,
+                });
+                examples.last_mut()
+            }
+            Some(input) => examples.iter_mut().find(|e| e.input == input),
+            None => examples.last_mut(),
+        };
+        if let Some(example) = example {
+            match part {
+                0 => example.part1 = answer,
+                _ => example.part2 = answer,
+            }
+        }
+    }
+    examples
+}
+
+/// Decoded contents of all `<pre><code>` blocks.
+fn code_blocks(html: &str) -> Vec<String> {
+    html.split("<pre><code>")
+        .skip(1)
+        .filter_map(|rest| Some(decode(&strip_tags(rest.split_once("</code></pre>")?.0))))
+        .collect()
+}
+
+fn last_emphasised_code(html: &str) -> Option<String> {
+    let outside_pre: String = html
+        .split("<pre>")
+        .map(|rest| rest.split_once("</pre>").map_or(rest, |(_, after)| after))
+        .collect();
+    ["<code><em>", "<em><code>"]
+        .into_iter()
+        .filter_map(|start| {
+            let (before, rest) = outside_pre.rsplit_once(start)?;
+            let (answer, _) = rest.split_once('<')?;
+            Some((before.len(), decode(answer)))
+        })
+        .max()
+        .map(|(_, answer)| answer)
+}
+
+/// Removes all HTML tags, keeping the text between them as is.
+pub(crate) fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Replaces the HTML entities used on the website.
+fn decode(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some((before, after)) = rest.split_once('&') {
+        decoded.push_str(before);
+        let entity = after.split_once(';').and_then(|(name, after)| {
+            let c = match name {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => {
+                    let code = match name.strip_prefix("#x") {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => name.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, after))
+        });
+        match entity {
+            Some((c, after)) => {
+                decoded.push(c);
+                rest = after;
+            }
+            None => {
+                decoded.push('&');
+                rest = after;
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Converts the HTML of a description article into Markdown.
+pub fn to_markdown(html: &str) -> String {
+    let mut markdown = String::new();
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut links = Vec::new();
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        let (text, after) = rest.split_once('<').unwrap_or((rest, ""));
+        markdown.push_str(&decode(text));
+        let Some((tag, after)) = after.split_once('>') else {
+            break;
+        };
+        rest = after;
+
+        let (name, attributes) = tag.split_once(' ').unwrap_or((tag, ""));
+        match name {
+            "h2" => markdown.push_str("## "),
+            "/h2" | "/p" => markdown.push_str("\n\n"),
+            "pre" => {
+                markdown.push_str("```\n");
+                in_pre = true;
+            }
+            "/pre" => {
+                if !markdown.ends_with('\n') {
+                    markdown.push('\n');
+                }
+                markdown.push_str("```\n\n");
+                in_pre = false;
+            }
+            "code" | "/code" if !in_pre => {
+                markdown.push('`');
+                in_code = name == "code";
+            }
+            "em" | "/em" if !in_pre && !in_code => markdown.push('*'),
+            "li" => markdown.push_str("- "),
+            "/li" | "/ul" => markdown.push('\n'),
+            "a" => {
+                let href = attributes
+                    .split_once("href=\"")
+                    .and_then(|(_, href)| href.split_once('"'))
+                    .map(|(href, _)| decode(href));
+                markdown.push('[');
+                links.push(href);
+            }
+            "/a" => match links.pop().flatten() {
+                Some(href) => markdown.push_str(&format!("]({href})")),
+                None => markdown.push(']'),
+            },
+            _ => {}
+        }
+    }
+
+    // Line breaks between the tags would otherwise add up to several empty lines
+    while markdown.contains("\n\n\n") {
+        markdown = markdown.replace("\n\n\n", "\n\n");
+    }
+    markdown.trim().to_string() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_examples, save_description, to_markdown};
+    use crate::examples::Example;
+    use indoc::indoc;
+    use std::fs;
+    use std::path::Path;
+
+    const DAY_01: &str = include_str!("../tests/fixtures/2023_01.html");
+    const DAY_04: &str = include_str!("../tests/fixtures/2023_04_part1.html");
+
+    fn example(day: u8, number: u32, part1: Option<&str>, part2: Option<&str>) -> Example {
+        Example {
+            part1: part1.map(str::to_string),
+            part2: part2.map(str::to_string),
+            ..Example::load(Path::new("input/2023"), day, number).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_extract_examples() {
+        assert_eq!(
+            extract_examples(DAY_01),
+            [
+                example(1, 1, Some("142"), None),
+                example(1, 2, None, Some("281")),
+            ]
+        );
+        // Before solving part one, the page doesn't contain part two yet
+        assert_eq!(extract_examples(DAY_04), [example(4, 1, Some("13"), None)]);
+    }
+
+    #[test]
+    fn test_save_description() {
+        let dir =
+            std::env::temp_dir().join(format!("adventoffrog-description-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("2023")).unwrap();
+        // Saved before part two was known, with a hand-edited input
+        let example_2 = dir.join("2023/01_example_2.txt");
+        fs::write(&example_2, "+++\npart1 = \"7\"\n+++\nseven\n").unwrap();
+
+        save_description(&dir, 2023, 1, DAY_01).unwrap();
+        let example_1 = Example::load(&dir.join("2023"), 1, 1).unwrap();
+        assert_eq!(example_1, example(1, 1, Some("142"), None));
+        let merged = "+++\npart1 = \"7\"\npart2 = \"281\"\n+++\nseven\n";
+        assert_eq!(fs::read_to_string(&example_2).unwrap(), merged);
+
+        // Nothing new to add the second time
+        save_description(&dir, 2023, 1, DAY_01).unwrap();
+        assert_eq!(fs::read_to_string(&example_2).unwrap(), merged);
+        assert!(dir.join("2023/01.md").is_file());
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let html = super::articles(DAY_01)[1];
+        let markdown = to_markdown(html);
+        assert!(markdown.starts_with(indoc! {r#"
+            ## --- Part Two ---
+
+            Your calculation isn't quite right. It looks like some of the digits are actually *spelled out with letters*: `one`, `two`,"#}));
+        assert!(markdown.contains(indoc! {"
+            ```
+            two1nine
+            eightwothree
+        "}));
+        assert!(markdown.ends_with(indoc! {"
+            7pqrstsixteen
+            ```
+
+            In this example, the calibration values are `29`, `83`, `13`, `24`, `42`, `14`, and `76`. Adding these together produces `281`.
+
+            *What is the sum of all of the calibration values?* See the [about page](/2023/about) & try again.
+        "}));
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+const HEADER_DELIMITER: &str = "+++";
+
+/// An example input, stored as `<input_path>/<year>/<day>_example_<number>.txt`.
+///
+/// The file may start with a TOML header declaring the expected answers:
+///
+/// ```text
+/// +++
+/// part1 = 142
+/// part2 = "281"
+/// +++
+/// 1abc2
+/// pqr3stu8vwx
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub number: u32,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub input: String,
+}
+
+impl Example {
+    pub fn load(dir: &Path, day: u8, number: u32) -> Result<Self> {
+        let path = dir.join(file_name(day, number));
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Invalid path: {path:?}"))?;
+        Self::parse(number, &content).with_context(|| format!("Invalid example: {path:?}"))
+    }
+
+    pub fn parse(number: u32, content: &str) -> Result<Self> {
+        let mut example = Example {
+            number,
+            part1: None,
+            part2: None,
+            input: content.to_string(),
+        };
+
+        // Lines keep their line break, so the input stays as is, `\r\n` included
+        let mut lines = content.split_inclusive('\n');
+        let is_delimiter = |line: &str| line.trim_end_matches(['\n', '\r']) == HEADER_DELIMITER;
+        if !lines.next().is_some_and(is_delimiter) {
+            return Ok(example);
+        }
+        let mut header = String::new();
+        loop {
+            match lines.next() {
+                Some(line) if is_delimiter(line) => break,
+                Some(line) => header.push_str(line),
+                None => bail!("Header isn't terminated by `{HEADER_DELIMITER}`"),
+            }
+        }
+        example.input = lines.collect();
+
+        for (key, value) in header.parse::<toml::Table>()? {
+            let slot = match key.as_str() {
+                "part1" => &mut example.part1,
+                "part2" => &mut example.part2,
+                _ => bail!("Unknown key `{key}` in header"),
+            };
+            *slot = Some(match value {
+                toml::Value::String(answer) => answer,
+                toml::Value::Integer(answer) => answer.to_string(),
+                value => bail!("Answer for `{key}` must be a string or an integer, not {value}"),
+            });
+        }
+        Ok(example)
+    }
+
+    pub fn expected(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Takes over the answers of `other` this example doesn't declare yet. Returns whether any
+    /// were added.
+    pub fn add_missing_answers(&mut self, other: &Example) -> bool {
+        let mut added = false;
+        for (answer, other) in [
+            (&mut self.part1, &other.part1),
+            (&mut self.part2, &other.part2),
+        ] {
+            if answer.is_none() && other.is_some() {
+                answer.clone_from(other);
+                added = true;
+            }
+        }
+        added
+    }
+
+    /// The file content, with a header if any answers are known.
+    pub fn to_file_content(&self) -> String {
+        if self.part1.is_none() && self.part2.is_none() {
+            return self.input.clone();
+        }
+
+        let mut content = format!("{HEADER_DELIMITER}\n");
+        for (part, answer) in [(1, &self.part1), (2, &self.part2)] {
+            if let Some(answer) = answer {
+                let answer = toml::Value::String(answer.clone());
+                content.push_str(&format!("part{part} = {answer}\n"));
+            }
+        }
+        content + HEADER_DELIMITER + "\n" + &self.input
+    }
+}
+
+pub fn file_name(day: u8, number: u32) -> String {
+    format!("{day:0>2}_example_{number}.txt")
+}
+
+/// Loads all examples of a day from `dir`, ordered by number.
+pub fn find(dir: &Path, day: u8) -> Result<Vec<Example>> {
+    let prefix = format!("{day:0>2}_example_");
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {dir:?}")),
+    };
+
+    let mut numbers = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name();
+        let number = name.to_str().and_then(|name| {
+            name.strip_prefix(&prefix)?
+                .strip_suffix(".txt")?
+                .parse::<u32>()
+                .ok()
+        });
+        numbers.extend(number);
+    }
+    numbers.sort_unstable();
+
+    numbers
+        .into_iter()
+        .map(|number| Example::load(dir, day, number))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Example;
+    use crate::registry;
+    use crate::runner::Parts;
+    use indoc::indoc;
+    use std::path::Path;
+
+    #[test]
+    fn test_header() {
+        let content = indoc! {r#"
+            +++
+            part1 = 142
+            part2 = "a,b"
+            +++
+            1abc2
+            +++
+        "#};
+        let example = Example::parse(1, content).unwrap();
+        assert_eq!(example.expected(1), Some("142"));
+        assert_eq!(example.expected(2), Some("a,b"));
+        assert_eq!(example.input, "1abc2\n+++\n");
+        assert_eq!(
+            example.to_file_content(),
+            content.replace("part1 = 142", "part1 = \"142\"")
+        );
+
+        let example = Example::parse(2, "#.#\n").unwrap();
+        assert_eq!((example.expected(1), example.expected(2)), (None, None));
+        assert_eq!(example.to_file_content(), "#.#\n");
+
+        // Checked out on Windows
+        let example = Example::parse(3, &content.replace('\n', "\r\n")).unwrap();
+        assert_eq!(example.expected(1), Some("142"));
+        assert_eq!(example.expected(2), Some("a,b"));
+        assert_eq!(example.input, "1abc2\r\n+++\r\n");
+
+        assert!(Example::parse(1, "+++\npart3 = 1\n+++\n").is_err());
+        assert!(Example::parse(1, "+++\npart1 = 1\n").is_err());
+    }
+
+    /// Runs every registered puzzle on all of its examples and compares the declared answers.
+    #[test]
+    fn test_examples() {
+        let mut failures = Vec::new();
+        let mut checked = 0;
+        for entry in registry::all() {
+            let dir = Path::new("input").join(entry.year.to_string());
+            for example in super::find(&dir, entry.day).unwrap() {
+                let name = format!(
+                    "{} day {:0>2} example {}",
+                    entry.year, entry.day, example.number
+                );
+                let mut runs = vec![];
+                if example.part1.is_some() {
+                    runs.push(Parts::One);
+                }
+                if example.part2.is_some() {
+                    runs.push(Parts::Two);
+                }
+                if runs.len() == 2 {
+                    // Also covers solving both parts at once
+                    runs.push(Parts::Both);
+                }
+
+                for parts in runs {
+                    let report = match (entry.solver)(&example.input, parts) {
+                        Ok(report) => report,
+                        Err(e) => {
+                            failures.push(format!("{name}: {e:#}"));
+                            continue;
+                        }
+                    };
+                    for (part, part_report) in report.parts() {
+                        checked += 1;
+                        let expected = example.expected(part);
+                        match &part_report.answer {
+                            Ok(answer) if Some(answer.as_str()) == expected => {}
+                            answer => failures.push(format!(
+                                "{name} part {part}: expected {expected:?}, got {answer:?}"
+                            )),
+                        }
+                    }
+                }
+            }
+        }
+
+        assert!(checked > 0, "No examples with answers found");
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,109 @@
+use std::ops::Range;
+
+/// Writes a random but valid input of the given size. What the size counts depends on the day,
+/// e.g. lines or cards.
+///
+/// A puzzle offers one via [`crate::Puzzle::GENERATOR`], which ends up in its
+/// [`crate::registry::PuzzleEntry`].
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// A small deterministic random number generator, [SplitMix64].
+///
+/// Good enough for generating inputs and, unlike the generators of the `rand` crate, guaranteed
+/// to produce the same numbers from the same seed forever.
+///
+/// [SplitMix64]: https://prng.di.unimi.it/splitmix64.c
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "Empty range {range:?}");
+        // Multiply-shift instead of modulo, which is biased towards small numbers
+        let offset = (u128::from(self.next_u64()) * u128::from(range.end - range.start)) >> 64;
+        range.start + offset as u64
+    }
+
+    /// An index below `len`, which must not be zero.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0..len as u64) as usize
+    }
+
+    /// True with a probability of `numerator / denominator`.
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.range(0..denominator) < numerator
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+    use crate::registry;
+    use crate::runner::Parts;
+
+    #[test]
+    fn test_splitmix64() {
+        // Reference values of https://prng.di.unimi.it/splitmix64.c
+        let mut rng = Rng::new(1234567);
+        let numbers: Vec<_> = (0..3).map(|_| rng.next_u64()).collect();
+        assert_eq!(
+            numbers,
+            [
+                6457827717110365317,
+                3203168211198807973,
+                9817491932198370423
+            ]
+        );
+
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            assert!((10..13).contains(&rng.range(10..13)));
+        }
+    }
+
+    #[test]
+    fn test_generators() {
+        for entry in registry::all() {
+            let Some(generate) = entry.generator else {
+                continue;
+            };
+            for seed in 0..5 {
+                let input = generate(&mut Rng::new(seed), 30);
+                assert_eq!(input, generate(&mut Rng::new(seed), 30));
+
+                let name = format!("{} day {} with seed {seed}", entry.year, entry.day);
+                let report = (entry.solver)(&input, Parts::Both)
+                    .unwrap_or_else(|e| panic!("Invalid input for {name}: {e:#}\n{input}"));
+                for (part, part_report) in report.parts() {
+                    if let Err(e) = &part_report.answer {
+                        panic!("Part {part} of {name} failed: {e:#}\n{input}");
+                    }
+                }
+            }
+        }
+    }
+}
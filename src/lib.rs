@@ -0,0 +1,94 @@
+//! Solutions for Advent of Code.
+//!
+//! Every puzzle implements [`Puzzle`] and is registered in [`registry`], which the runner in the
+//! `adventoffrog` binary uses to find them. The day modules can also be used directly:
+//!
+//! ```
+//! use adventoffrog::Puzzle;
+//! use adventoffrog::year2023::day06::Day;
+//!
+//! let day = Day::parse("Time:      7  15   30\nDistance:  9  40  200\n").unwrap();
+//! assert_eq!(day.solve_problem_1().unwrap(), 288);
+//! ```
+
+use anyhow::{Context, Result};
+use generate::Generator;
+use std::ops::RangeInclusive;
+
+pub mod alloc_stats;
+pub mod answers;
+pub mod bench;
+pub mod bench_history;
+pub mod checked;
+pub mod client;
+pub mod config;
+pub mod description;
+pub mod examples;
+pub mod generate;
+pub mod matrix;
+pub mod output;
+pub mod parse_error;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod submit;
+pub mod util;
+pub mod watch;
+pub mod year2022;
+pub mod year2023;
+
+/// Range of valid day numbers.
+pub const DAYS: RangeInclusive<u8> = 1..=31;
+
+pub trait Puzzle<'a>: Sized {
+    const YEAR: u32;
+    const DAY: u8;
+    /// Writes random inputs for the `gen` command and tests, if the puzzle has a generator.
+    const GENERATOR: Option<Generator> = None;
+
+    type Sol1Type: Answer;
+    type Sol2Type: Answer;
+
+    fn parse(input: &'a str) -> Result<Self>;
+    fn solve_problem_1(&self) -> Self::Sol1Type;
+    fn solve_problem_2(&self) -> Self::Sol2Type;
+
+    /// Solves both parts in one go.
+    ///
+    /// Puzzles whose parts share intermediate results can implement this to compute them only
+    /// once. Returns `None` if the parts can only be solved separately.
+    fn solve_both(&self) -> Option<(Self::Sol1Type, Self::Sol2Type)> {
+        None
+    }
+}
+
+/// A solution that can be turned into the answer string expected by the puzzle website.
+pub trait Answer {
+    fn into_answer(self) -> Result<String>;
+}
+
+macro_rules! impl_answer_via_display {
+    ($($t:ty),*) => {
+        $(
+            impl Answer for $t {
+                fn into_answer(self) -> Result<String> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_via_display!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, String);
+
+impl<T: Answer> Answer for Option<T> {
+    fn into_answer(self) -> Result<String> {
+        self.context("No solution found")?.into_answer()
+    }
+}
+
+impl<T: Answer> Answer for Result<T> {
+    fn into_answer(self) -> Result<String> {
+        self?.into_answer()
+    }
+}
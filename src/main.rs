@@ -1,107 +1,858 @@
-use anyhow::{bail, Context, Result};
-use clap::Parser;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::builder::PossibleValuesParser;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 
-use std::fmt::Debug;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::hint::black_box;
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-mod util;
-mod year2022;
-mod year2023;
+use adventoffrog::alloc_stats::{self, CountingAllocator};
+use adventoffrog::answers::AnswerStore;
+use adventoffrog::bench::BenchOptions;
+use adventoffrog::bench_history::{self, BenchHistory, BenchRun};
+use adventoffrog::client::{self, Client};
+use adventoffrog::config::{self, Config, ConfigFile};
+use adventoffrog::description;
+use adventoffrog::examples::{self, Example};
+use adventoffrog::generate::Rng;
+use adventoffrog::matrix::{self, Matrix};
+use adventoffrog::output::{self, Format, Record};
+use adventoffrog::registry;
+use adventoffrog::runner::{self, DaySelection, Outcome, Parts, Summary};
+use adventoffrog::scaffold;
+use adventoffrog::submit::{self, Feedback, History};
+use adventoffrog::watch;
 
+// Only counts once enabled by `--alloc-stats`
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+
+/// Rust solutions for Advent of Code
 #[derive(Parser)]
+#[command(
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the module and input files for a new day
+    New {
+        #[arg(short, long)]
+        year: u32,
+
+        #[arg(short, long)]
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=31))]
+        day: u8,
+
+        /// Source directory of this crate
+        #[arg(long, default_value = "src")]
+        src_path: PathBuf,
+
+        #[arg(short, long, default_value = "input")]
+        input_path: PathBuf,
+    },
+    /// Download the personal input of a day, unless it's already cached
+    Fetch {
+        #[arg(short, long, default_value_t = registry::latest_year())]
+        year: u32,
+
+        #[arg(short, long)]
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        #[arg(short, long, default_value = "input")]
+        input_path: PathBuf,
+
+        /// File containing the session token, if `AOC_SESSION` isn't set
+        #[arg(long)]
+        session_file: Option<PathBuf>,
+    },
+    /// Save the puzzle description as Markdown and extract its examples
+    Description {
+        #[arg(short, long, default_value_t = registry::latest_year())]
+        year: u32,
+
+        #[arg(short, long)]
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        #[arg(short, long, default_value = "input")]
+        input_path: PathBuf,
+
+        /// Read the page from a saved HTML file instead of downloading it
+        #[arg(long, conflicts_with = "session_file")]
+        html_file: Option<PathBuf>,
+
+        /// File containing the session token, if `AOC_SESSION` isn't set
+        #[arg(long)]
+        session_file: Option<PathBuf>,
+    },
+    /// Print a random but valid input, e.g. for stress testing and benchmarking
+    Gen {
+        #[arg(short, long, default_value_t = registry::latest_year())]
+        #[arg(value_parser = parse_year)]
+        year: u32,
+
+        #[arg(short, long)]
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        /// Scale of the input, e.g. the number of lines or cards, depending on the day
+        #[arg(long, default_value_t = 100)]
+        size: usize,
+
+        /// Seed of the random number generator, the same seed always gives the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Print a completion script for the given shell
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Print the man page in roff format
+    Manpage,
+    /// Inspect the configuration files
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Submit an answer and record the attempt in the submission history
+    Submit {
+        #[arg(short, long, default_value_t = registry::latest_year())]
+        #[arg(value_parser = parse_year)]
+        year: u32,
+
+        #[arg(short, long)]
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// The answer to submit. Solves the part with the personal input if not given.
+        answer: Option<String>,
+
+        #[arg(short, long, default_value = "input")]
+        input_path: PathBuf,
+
+        #[arg(long, default_value = "answers")]
+        answers_path: PathBuf,
+
+        /// File containing the session token, if `AOC_SESSION` isn't set
+        #[arg(long)]
+        session_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the effective settings and where they come from
+    Show,
+}
+
+#[derive(clap::Args)]
 struct Args {
-    #[arg(short, long)]
+    #[arg(short, long, required_unless_present_any = ["all", "days", "list"])]
     #[arg(value_parser = clap::value_parser!(u8).range(1..=31))]
-    day: u8,
+    day: Option<u8>,
+
+    /// Run all implemented days of the year and print a summary table
+    #[arg(long, conflicts_with_all = ["day", "days"])]
+    all: bool,
+
+    /// Run the given days and print a summary table, e.g. `1-5,7`
+    #[arg(long, conflicts_with = "day")]
+    days: Option<DaySelection>,
 
-    #[arg(short, long, default_value_t = 2023)]
-    #[arg(value_parser = clap::value_parser!(u32).range(2022..=2023))]
+    #[arg(short, long, default_value_t = registry::latest_year())]
+    #[arg(value_parser = parse_year)]
     year: u32,
 
-    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
-    example: Option<u8>,
+    /// List all implemented puzzles
+    #[arg(long, conflicts_with_all = ["day", "days", "all"])]
+    list: bool,
+
+    /// Run on `<day>_example_<EXAMPLE>.txt` instead of the personal input
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    example: Option<u32>,
 
     #[arg(short, long, default_value = "input")]
     input_path: PathBuf,
+
+    /// Download missing inputs before solving
+    #[arg(long, conflicts_with_all = ["example", "input_file"])]
+    fetch: bool,
+
+    /// File containing the session token, if `AOC_SESSION` isn't set
+    #[arg(long, requires = "fetch")]
+    session_file: Option<PathBuf>,
+
+    /// Read the input from this file instead of the input directory. Use `-` for stdin.
+    #[arg(long, requires = "day", conflicts_with_all = ["example", "check", "record"])]
+    input_file: Option<PathBuf>,
+
+    /// Run on the inputs of all accounts, e.g. `05.alice.txt` or `05/bob.txt`, and compare the
+    /// answers against the ones recorded for each account
+    #[arg(long, conflicts_with_all = ["example", "input_file", "fetch", "check", "format", "bench"])]
+    all_inputs: bool,
+
+    /// Compare the answers against the recorded ones and fail on any mismatch
+    #[arg(long, conflicts_with_all = ["example", "record"])]
+    check: bool,
+
+    /// Record the answers as the accepted ones
+    #[arg(long, conflicts_with = "example")]
+    record: bool,
+
+    #[arg(long, default_value = "answers")]
+    answers_path: PathBuf,
+
+    /// Maximum time in seconds each day may take when running multiple days
+    #[arg(long, value_name = "SECONDS", conflicts_with = "day")]
+    timeout: Option<f64>,
+
+    /// Which parts to solve
+    #[arg(long, value_enum, default_value_t)]
+    part: Parts,
+
+    /// Output format of the answers and timings
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+
+    /// Report the allocations made while parsing and solving each part
+    #[arg(long, requires = "day", conflicts_with = "format")]
+    alloc_stats: bool,
+
+    /// Benchmark parsing and each part over the given number of iterations. Days solving both
+    /// parts in one go are benchmarked as a single "Both parts" phase
+    #[arg(long, value_name = "ITERATIONS", conflicts_with_all = ["check", "record", "format", "alloc_stats"])]
+    bench: Option<u32>,
+
+    /// Number of unmeasured iterations per phase before benchmarking
+    #[arg(long, default_value_t = 10, requires = "bench")]
+    warmup: u32,
+
+    /// Maximum time in seconds spent benchmarking each phase
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 5.0,
+        requires = "bench"
+    )]
+    bench_time: f64,
+
+    /// File the benchmark results get appended to
+    #[arg(long, default_value = "benchmarks/history.jsonl")]
+    bench_history: PathBuf,
+
+    /// Name this benchmark run, so it can be used as a baseline later
+    #[arg(long, value_name = "NAME", requires = "bench")]
+    save_baseline: Option<String>,
+
+    /// Compare against the last benchmark run, or the last one with the given name or commit
+    #[arg(long, value_name = "BASELINE", num_args = 0..=1, default_missing_value = "", requires = "bench")]
+    compare: Option<String>,
+
+    /// Changes of the median beyond this percentage get highlighted when comparing
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 5.0,
+        requires = "compare"
+    )]
+    noise_threshold: f64,
+
+    /// Keep running and solve again whenever the input file changes
+    #[arg(long, requires = "day")]
+    #[arg(conflicts_with_all = ["check", "record", "format", "alloc_stats", "bench"])]
+    watch: bool,
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let config_files = config::load(&config::search_paths())?;
+    let sources = apply_config(&mut cli, &matches, &config_files)?;
+
+    if let Some(command) = cli.command {
+        return match command {
+            Command::New {
+                year,
+                day,
+                src_path,
+                input_path,
+            } => scaffold::new_day(&src_path, &input_path, year, day),
+            Command::Fetch {
+                year,
+                day,
+                input_path,
+                session_file,
+            } => {
+                let client = Client::from_env(&input_path, session_file.as_deref())?;
+                if client::fetch_input(&client, &input_path, year, day)? {
+                    println!("Downloaded the input of {year} day {day}");
+                } else {
+                    println!("The input of {year} day {day} is already cached");
+                }
+                Ok(())
+            }
+            Command::Description {
+                year,
+                day,
+                input_path,
+                html_file,
+                session_file,
+            } => {
+                let html = match html_file {
+                    Some(file) => fs::read_to_string(&file)
+                        .with_context(|| format!("Invalid path: {file:?}"))?,
+                    None => {
+                        let client = Client::from_env(&input_path, session_file.as_deref())?;
+                        description::fetch_description(&client, year, day)?
+                    }
+                };
+                description::save_description(&input_path, year, day, &html)
+            }
+            Command::Gen {
+                year,
+                day,
+                size,
+                seed,
+            } => {
+                let entry = registry::find(year, day);
+                let Some(generate) = entry.and_then(|entry| entry.generator) else {
+                    bail!("There is no input generator for {year} day {day}");
+                };
+                print!("{}", generate(&mut Rng::new(seed), size));
+                Ok(())
+            }
+            Command::Completions { shell } => {
+                let mut command = completion_command();
+                let name = command.get_name().to_string();
+                clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+                Ok(())
+            }
+            Command::Manpage => {
+                clap_mangen::Man::new(Cli::command()).render(&mut io::stdout())?;
+                Ok(())
+            }
+            Command::Config {
+                command: ConfigCommand::Show,
+            } => {
+                show_config(&cli.args, &sources, &config_files);
+                Ok(())
+            }
+            Command::Submit {
+                year,
+                day,
+                part,
+                answer,
+                input_path,
+                answers_path,
+                session_file,
+            } => {
+                let answer = match answer {
+                    Some(answer) => answer,
+                    None => solve_part(&input_path, year, day, part)?,
+                };
+                let client = Client::from_env(&input_path, session_file.as_deref())?;
+                let mut history = History::load(&answers_path, year)?;
+
+                println!("Submitting {answer} for {year} day {day} part {part}");
+                let response = submit::submit(&client, &mut history, year, day, part, &answer)?;
+                println!("{}", response.message);
+                if let Some(wait) = response.wait {
+                    println!("Wait {wait:?} before submitting again");
+                }
+
+                match response.feedback {
+                    Feedback::Correct => {
+                        let mut store = AnswerStore::load(&answers_path, year)?;
+                        store.set(day, part, &answer);
+                        store.save()
+                    }
+                    feedback => bail!("The answer was not accepted: {feedback}"),
+                }
+            }
+        };
+    }
+
+    let args = cli.args;
+
+    if args.list {
+        for entry in registry::all() {
+            println!("{} day {:0>2}: {}", entry.year, entry.day, entry.name);
+        }
+        return Ok(());
+    }
+
+    if args.alloc_stats {
+        alloc_stats::enable();
+    }
+
+    let path = args.input_path.join(args.year.to_string());
+    let client = args
+        .fetch
+        .then(|| Client::from_env(&args.input_path, args.session_file.as_deref()))
+        .transpose()?;
+
+    if let Some(iterations) = args.bench {
+        let options = BenchOptions {
+            iterations,
+            warmup: args.warmup,
+            budget: Duration::try_from_secs_f64(args.bench_time)
+                .context("Invalid benchmark time")?,
+        };
+        return run_benchmarks(&args, client.as_ref(), &path, &options);
+    }
+
+    let timeout = args
+        .timeout
+        .map(Duration::try_from_secs_f64)
+        .transpose()
+        .context("Invalid timeout")?;
+
+    if args.all_inputs {
+        return run_matrix(&args, &path, timeout);
+    }
 
-    match args.year {
-        2022 => year_2022(args),
-        2023 => year_2023(args),
-        _ => bail!("Unknown year"),
+    let days = match (args.day, &args.days) {
+        (Some(day), _) => {
+            let Some(entry) = registry::find(args.year, day) else {
+                let days = registry::days(args.year).map(|day| day.to_string());
+                bail!(
+                    "Day {day} of {} is not implemented. Available days: {}",
+                    args.year,
+                    days.collect::<Vec<_>>().join(", ")
+                );
+            };
+            if args.watch {
+                let file = match &args.input_file {
+                    Some(file) if file.as_os_str() == "-" => bail!("Can't watch stdin"),
+                    Some(file) => file.clone(),
+                    None => match args.example {
+                        Some(number) => path.join(examples::file_name(day, number)),
+                        None => path.join(format!("{day:0>2}.txt")),
+                    },
+                };
+                // Download a missing input once, then only watch the local file
+                read_input(&args, client.as_ref(), &path, day)?;
+                return watch::watch(&file, entry.solver, args.part, || {
+                    read_input(&args, None, &path, day)
+                });
+            }
+
+            let input = read_input(&args, client.as_ref(), &path, day)?;
+            let report = (entry.solver)(&input, args.part);
+            if args.format == Format::Text {
+                let report = report?;
+                report.print();
+                return verify(&args, [(day, &Outcome::Solved(report))]);
+            }
+
+            let outcome = Outcome::from(report);
+            let records = Record::from_outcome(args.year, day, &outcome, args.part);
+            output::write_records(io::stdout().lock(), args.format, &records)?;
+            return match outcome {
+                Outcome::Failed(e) => Err(e),
+                outcome => verify(&args, [(day, &outcome)]),
+            };
+        }
+        (None, Some(days)) => days.clone(),
+        (None, None) => registry::days(args.year).collect(),
+    };
+
+    let mut summary = Summary::new(args.year);
+    for day in days.iter() {
+        let Some(entry) = registry::find(args.year, day) else {
+            summary.push(day, Outcome::Skipped("Not implemented".to_string()));
+            continue;
+        };
+
+        let outcome = match read_input(&args, client.as_ref(), &path, day) {
+            Ok(input) => runner::solve_isolated(entry.solver, input, args.part, timeout),
+            Err(e) => Outcome::Skipped(format!("{e:#}")),
+        };
+        summary.push(day, outcome);
     }
+    if args.format == Format::Text {
+        summary.print();
+    } else {
+        let records: Vec<_> = summary
+            .rows()
+            .flat_map(|(day, outcome)| Record::from_outcome(args.year, day, outcome, args.part))
+            .collect();
+        output::write_records(io::stdout().lock(), args.format, &records)?;
+    }
+
+    verify(&args, summary.rows())
 }
 
-fn load(path: &Path, day: u8, example: Option<u8>) -> Result<String> {
-    let path = path.join(format!(
-        "{:0>2}{}.txt",
-        day,
-        if let Some(x) = example {
-            format!("_example_{}", x)
-        } else {
-            "".to_string()
+fn run_benchmarks(
+    args: &Args,
+    client: Option<&Client>,
+    path: &Path,
+    options: &BenchOptions,
+) -> Result<()> {
+    let days = match (args.day, &args.days) {
+        (Some(day), _) => [day].into_iter().collect(),
+        (None, Some(days)) => days.clone(),
+        (None, None) => registry::days(args.year).collect(),
+    };
+
+    let mut history = BenchHistory::load(&args.bench_history)?;
+    let mut run = BenchRun::new(args.save_baseline.clone());
+    for day in days.iter() {
+        let Some(entry) = registry::find(args.year, day) else {
+            println!("{} day {day:0>2}: not implemented\n", args.year);
+            continue;
+        };
+        let input = match read_input(args, client, path, day) {
+            Ok(input) => input,
+            Err(e) if args.day.is_none() => {
+                println!("{} day {day:0>2}: skipped, {e:#}\n", args.year);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        println!("{} day {day:0>2}:", args.year);
+        let report = (entry.bencher)(&input, options, args.part)?;
+        report.print();
+        println!();
+        run.push(args.year, day, &report);
+    }
+
+    if let Some(baseline) = &args.compare {
+        let name = Some(baseline.as_str()).filter(|name| !name.is_empty());
+        match history.baseline(name) {
+            Some(baseline) => {
+                bench_history::print_comparison(baseline, &run, args.noise_threshold / 100.0)
+            }
+            None => println!("No baseline to compare against found"),
         }
-    ));
-    fs::read_to_string(&path).with_context(|| format!("Invalid path: {path:?}"))
+    }
+
+    history.append(run)
+}
+
+/// Solves a single part of a day with the personal input.
+fn solve_part(input_path: &Path, year: u32, day: u8, part: u8) -> Result<String> {
+    let entry = registry::find(year, day)
+        .with_context(|| format!("Day {day} of {year} is not implemented"))?;
+    let input = load(&input_path.join(year.to_string()), day, None)?;
+    let parts = if part == 1 { Parts::One } else { Parts::Two };
+    let report = (entry.solver)(&input, parts)?;
+    let (_, part_report) = report.parts().next().context("The part wasn't solved")?;
+    part_report
+        .answer
+        .as_ref()
+        .cloned()
+        .map_err(|e| anyhow!("Failed to solve part {part}: {e:#}"))
 }
 
-fn year_2022(args: Args) -> Result<()> {
-    use crate::year2022::*;
+/// Overrides settings not given on the command line with the ones from the config files.
+struct Configurer<'a> {
+    matches: &'a ArgMatches,
+    files: &'a [ConfigFile],
+    /// The config file each overridden setting came from, by argument id.
+    sources: BTreeMap<&'static str, PathBuf>,
+}
 
-    let path = args.input_path.join("2022");
-    let input = load(&path, args.day, args.example)?;
-    match args.day {
-        1 => solve::<day01::Day01>(&input),
-        _ => bail!("Unknown day"),
+impl Configurer<'_> {
+    fn set<T>(&mut self, id: &'static str, value: &mut T, get: impl Fn(&Config) -> Option<T>) {
+        if self.matches.value_source(id) == Some(ValueSource::CommandLine) {
+            return;
+        }
+        if let Some((configured, path)) = config::lookup(self.files, get) {
+            *value = configured;
+            self.sources.insert(id, path.to_path_buf());
+        }
+    }
+
+    fn set_session_file(&mut self, session_file: &mut Option<PathBuf>) {
+        self.set("session_file", session_file, |c| {
+            c.session_file.clone().map(Some)
+        });
     }
 }
 
-fn year_2023(args: Args) -> Result<()> {
-    use crate::year2023::*;
+fn apply_config(
+    cli: &mut Cli,
+    matches: &ArgMatches,
+    files: &[ConfigFile],
+) -> Result<BTreeMap<&'static str, PathBuf>> {
+    let mut configurer = Configurer {
+        matches,
+        files,
+        sources: BTreeMap::new(),
+    };
+
+    let args = &mut cli.args;
+    configurer.set("year", &mut args.year, |c| c.year);
+    configurer.set("input_path", &mut args.input_path, |c| c.input_path.clone());
+    configurer.set("answers_path", &mut args.answers_path, |c| {
+        c.answers_path.clone()
+    });
+    configurer.set("format", &mut args.format, |c| c.format);
+    configurer.set_session_file(&mut args.session_file);
+    configurer.set("warmup", &mut args.warmup, |c| c.bench.warmup);
+    configurer.set("bench_time", &mut args.bench_time, |c| c.bench.time);
+    configurer.set("bench_history", &mut args.bench_history, |c| {
+        c.bench.history.clone()
+    });
+    // `--format` conflicts with modes that only print text. A configured format is merely a
+    // default though, so those modes fall back to text instead of failing.
+    if let Some(path) = configurer.sources.get("format") {
+        let text_only = [
+            ("--alloc-stats", args.alloc_stats),
+            ("--all-inputs", args.all_inputs),
+            ("--bench", args.bench.is_some()),
+            ("--watch", args.watch),
+        ];
+        if let Some((flag, _)) = text_only.into_iter().find(|(_, set)| *set) {
+            eprintln!("Ignoring the format configured in {path:?}, {flag} only prints text");
+            args.format = Format::default();
+            configurer.sources.remove("format");
+        }
+    }
+    if let Some(path) = configurer.sources.get("year") {
+        parse_year(&args.year.to_string()).with_context(|| format!("Invalid year in {path:?}"))?;
+    }
+    let sources = configurer.sources;
 
-    let path = args.input_path.join("2023");
-    let input = load(&path, args.day, args.example)?;
-    match args.day {
-        1 => solve::<day01::Day>(&input),
-        2 => solve::<day02::Day>(&input),
-        3 => solve::<day03::Day>(&input),
-        4 => solve::<day04::Day>(&input),
-        5 => solve::<day05::Day>(&input),
-        6 => solve::<day06::Day>(&input),
-        7 => solve::<day07::Day>(&input),
-        _ => bail!("Unknown day"),
+    let Some((_, matches)) = matches.subcommand() else {
+        return Ok(sources);
+    };
+    let mut configurer = Configurer {
+        matches,
+        files,
+        sources: BTreeMap::new(),
+    };
+    match &mut cli.command {
+        Some(Command::New { input_path, .. }) => {
+            configurer.set("input_path", input_path, |c| c.input_path.clone());
+        }
+        Some(
+            Command::Fetch {
+                year,
+                input_path,
+                session_file,
+                ..
+            }
+            | Command::Description {
+                year,
+                input_path,
+                session_file,
+                ..
+            },
+        ) => {
+            configurer.set("year", year, |c| c.year);
+            configurer.set("input_path", input_path, |c| c.input_path.clone());
+            configurer.set_session_file(session_file);
+        }
+        Some(Command::Submit {
+            year,
+            input_path,
+            answers_path,
+            session_file,
+            ..
+        }) => {
+            configurer.set("year", year, |c| c.year);
+            configurer.set("input_path", input_path, |c| c.input_path.clone());
+            configurer.set("answers_path", answers_path, |c| c.answers_path.clone());
+            configurer.set_session_file(session_file);
+        }
+        Some(Command::Gen { year, .. }) => {
+            configurer.set("year", year, |c| c.year);
+        }
+        Some(Command::Completions { .. } | Command::Manpage | Command::Config { .. }) | None => {}
     }
+
+    Ok(sources)
 }
 
-fn solve<'a, T: Puzzle<'a>>(input: &'a str) -> Result<()> {
-    let start = std::time::Instant::now();
-    let day_xy = black_box(T::parse(input))?;
-    let parse_time = std::time::Instant::now() - start;
-    println!("Parsed in {parse_time:?}");
+fn show_config(args: &Args, sources: &BTreeMap<&'static str, PathBuf>, files: &[ConfigFile]) {
+    let path = |path: &Path| path.display().to_string();
+    let session_file = match &args.session_file {
+        Some(file) => path(file),
+        None => client::default_session_file()
+            .as_deref()
+            .map(path)
+            .unwrap_or_default(),
+    };
+    let format = args.format.to_possible_value().unwrap();
+
+    let settings = [
+        ("year", "year", args.year.to_string()),
+        ("input_path", "input_path", path(&args.input_path)),
+        ("answers_path", "answers_path", path(&args.answers_path)),
+        ("format", "format", format.get_name().to_string()),
+        ("session_file", "session_file", session_file),
+        ("bench.warmup", "warmup", args.warmup.to_string()),
+        ("bench.time", "bench_time", args.bench_time.to_string()),
+        ("bench.history", "bench_history", path(&args.bench_history)),
+    ];
+    let rows: Vec<_> = settings
+        .into_iter()
+        .map(|(key, id, value)| {
+            let source = match sources.get(id) {
+                Some(file) => path(file),
+                None => "default".to_string(),
+            };
+            [key.to_string(), value, source]
+        })
+        .collect();
+    runner::print_table(["Setting", "Value", "Source"], &rows, None);
 
-    let start = std::time::Instant::now();
-    let solution_1 = black_box(day_xy.solve_problem_1());
-    let s1_time = std::time::Instant::now() - start;
-    println!("Solution 1 is: {solution_1:?} (Computed in {s1_time:?})");
+    println!();
+    for file in config::search_paths() {
+        let loaded = files.iter().any(|loaded| loaded.path == file);
+        let status = if loaded { "loaded" } else { "not found" };
+        println!("{}: {status}", file.display());
+    }
+}
 
-    let start = std::time::Instant::now();
-    let solution_2 = black_box(day_xy.solve_problem_2());
-    let s2_time = std::time::Instant::now() - start;
-    println!("Solution 2 is: {solution_2:?} (Computed in {s2_time:?})");
-    println!("Total time: {:?}", parse_time + s1_time + s2_time);
+fn run_matrix(args: &Args, path: &Path, timeout: Option<Duration>) -> Result<()> {
+    let days = match (args.day, &args.days) {
+        (Some(day), _) => [day].into_iter().collect(),
+        (None, Some(days)) => days.clone(),
+        (None, None) => registry::days(args.year).collect(),
+    };
+
+    let mut matrix = Matrix::new(args.year, &args.answers_path);
+    for day in days.iter() {
+        let Some(entry) = registry::find(args.year, day) else {
+            continue;
+        };
+        for (account, file) in matrix::find_inputs(path, day)? {
+            let outcome = match fs::read_to_string(&file) {
+                Ok(input) => runner::solve_isolated(entry.solver, input, args.part, timeout),
+                Err(e) => Outcome::Skipped(format!("Failed to read {file:?}: {e}")),
+            };
+            matrix.push(day, &account, outcome, args.part)?;
+        }
+    }
+    matrix.print();
+
+    if args.record {
+        return matrix.record();
+    }
+    match matrix.mismatches() {
+        0 => Ok(()),
+        n => bail!("{n} answer(s) did not match the recorded ones"),
+    }
+}
+
+/// Handles `--check` and `--record` for the given outcomes. Only solved days get recorded.
+fn verify<'a>(args: &Args, outcomes: impl IntoIterator<Item = (u8, &'a Outcome)>) -> Result<()> {
+    if !args.check && !args.record {
+        return Ok(());
+    }
+
+    // Keep stdout clean for machine readable output
+    let mut out: Box<dyn Write> = match args.format {
+        Format::Text => Box::new(io::stdout().lock()),
+        Format::Json | Format::Csv => Box::new(io::stderr().lock()),
+    };
+
+    let mut store = AnswerStore::load(&args.answers_path, args.year)?;
+    if args.record {
+        for (day, outcome) in outcomes {
+            if let Outcome::Solved(report) = outcome {
+                store.record_report(day, report, &mut out)?;
+            }
+        }
+        return store.save();
+    }
+
+    let mut failures = 0;
+    for (day, outcome) in outcomes {
+        failures += store.check_outcome(day, outcome, args.part, &mut out)?;
+    }
+    if failures > 0 {
+        bail!("{failures} answer(s) did not match the recorded ones");
+    }
     Ok(())
 }
 
-trait Puzzle<'a>: Sized {
-    type Sol1Type: Debug;
-    type Sol2Type: Debug;
+fn parse_year(s: &str) -> Result<u32> {
+    let year = s.parse()?;
+    if !registry::years().any(|y| y == year) {
+        let years = registry::years().map(|year| year.to_string());
+        bail!(
+            "No puzzles implemented for {year}. Available years: {}",
+            years.collect::<Vec<_>>().join(", ")
+        );
+    }
+    Ok(year)
+}
+
+/// The CLI definition with the implemented years and days as possible values of `--year` and
+/// `--day`, so the completion scripts offer them. `new`, `fetch` and `description` are left
+/// alone, they are used before a day is implemented.
+fn completion_command() -> clap::Command {
+    fn complete_puzzles(command: clap::Command) -> clap::Command {
+        if ["new", "fetch", "description"].contains(&command.get_name()) {
+            return command;
+        }
+
+        let years: Vec<_> = registry::years().map(|year| year.to_string()).collect();
+        let days: BTreeSet<_> = registry::all().map(|entry| entry.day).collect();
+        let days: Vec<_> = days.into_iter().map(|day| day.to_string()).collect();
+        command
+            .mut_args(|arg| match arg.get_id().as_str() {
+                "year" => arg.value_parser(PossibleValuesParser::new(years.clone())),
+                "day" => arg.value_parser(PossibleValuesParser::new(days.clone())),
+                _ => arg,
+            })
+            .mut_subcommands(complete_puzzles)
+    }
+
+    complete_puzzles(Cli::command())
+}
 
-    fn parse(input: &'a str) -> Result<Self>;
-    fn solve_problem_1(&self) -> Self::Sol1Type;
-    fn solve_problem_2(&self) -> Self::Sol2Type;
+/// Reads the input of `day`, either from `--input-file` or from the input directory.
+///
+/// With a `client`, missing inputs get downloaded into the input directory first.
+fn read_input(args: &Args, client: Option<&Client>, path: &Path, day: u8) -> Result<String> {
+    match &args.input_file {
+        Some(file) if file.as_os_str() == "-" => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .context("Failed to read input from stdin")?;
+            Ok(input)
+        }
+        Some(file) => fs::read_to_string(file).with_context(|| format!("Invalid path: {file:?}")),
+        None => {
+            if let Some(client) = client {
+                client::fetch_input(client, &args.input_path, args.year, day)?;
+            }
+            load(path, day, args.example)
+        }
+    }
+}
+
+fn load(path: &Path, day: u8, example: Option<u32>) -> Result<String> {
+    if let Some(number) = example {
+        return Ok(Example::load(path, day, number)?.input);
+    }
+
+    let path = path.join(format!("{day:0>2}.txt"));
+    fs::read_to_string(&path).with_context(|| format!("Invalid path: {path:?}"))
 }
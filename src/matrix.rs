@@ -0,0 +1,291 @@
+use crate::answers::{AnswerStore, Verdict};
+use crate::runner::{print_table, Outcome, Parts};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the account whose input is the plain `<day>.txt`.
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// Finds all inputs of a day in `dir`, named after the account they belong to.
+///
+/// Accepted are `<day>.txt` for [`DEFAULT_ACCOUNT`], `<day>.<account>.txt` and
+/// `<day>/<account>.txt`. The default account comes first, the others are sorted by name.
+pub fn find_inputs(dir: &Path, day: u8) -> Result<Vec<(String, PathBuf)>> {
+    let day = format!("{day:0>2}");
+    let mut inputs = Vec::new();
+
+    let default = dir.join(format!("{day}.txt"));
+    if default.is_file() {
+        inputs.push((DEFAULT_ACCOUNT.to_string(), default));
+    }
+
+    let mut accounts = Vec::new();
+    for (dir, prefix) in [
+        (dir.to_path_buf(), format!("{day}.")),
+        (dir.join(&day), String::new()),
+    ] {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {dir:?}")),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            let account = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&prefix)?.strip_suffix(".txt"))
+                .filter(|account| !account.is_empty() && !account.contains('.'))
+                .map(str::to_string);
+            if let Some(account) = account.filter(|_| path.is_file()) {
+                accounts.push((account, path));
+            }
+        }
+    }
+    accounts.sort();
+    inputs.extend(accounts);
+
+    Ok(inputs)
+}
+
+struct Row {
+    day: u8,
+    account: String,
+    outcome: Outcome,
+    verdicts: Vec<(u8, Verdict)>,
+}
+
+/// The outcomes of running days on the inputs of several accounts, each checked against the
+/// answers recorded for that account.
+pub struct Matrix {
+    year: u32,
+    answers_path: PathBuf,
+    stores: BTreeMap<String, AnswerStore>,
+    rows: Vec<Row>,
+}
+
+impl Matrix {
+    /// The answers of [`DEFAULT_ACCOUNT`] are in `answers_path` as usual, those of other accounts
+    /// in `<answers_path>/<account>`.
+    pub fn new(year: u32, answers_path: &Path) -> Self {
+        Matrix {
+            year,
+            answers_path: answers_path.to_path_buf(),
+            stores: BTreeMap::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn store(&mut self, account: &str) -> Result<&mut AnswerStore> {
+        if !self.stores.contains_key(account) {
+            let path = match account {
+                DEFAULT_ACCOUNT => self.answers_path.clone(),
+                account => self.answers_path.join(account),
+            };
+            let store = AnswerStore::load(&path, self.year)?;
+            self.stores.insert(account.to_string(), store);
+        }
+        Ok(self.stores.get_mut(account).unwrap())
+    }
+
+    /// Adds the outcome of solving `parts` of a day. A day that didn't solve fails the parts
+    /// with a recorded answer.
+    pub fn push(&mut self, day: u8, account: &str, outcome: Outcome, parts: Parts) -> Result<()> {
+        let verdicts = self.store(account)?.verdicts(day, &outcome, parts);
+
+        self.rows.push(Row {
+            day,
+            account: account.to_string(),
+            outcome,
+            verdicts,
+        });
+        Ok(())
+    }
+
+    /// Number of answers that differ from the recorded ones.
+    pub fn mismatches(&self) -> usize {
+        self.rows
+            .iter()
+            .flat_map(|row| &row.verdicts)
+            .filter(|(_, verdict)| matches!(verdict, Verdict::Fail { .. }))
+            .count()
+    }
+
+    /// Records all computed answers as the accepted ones of their account.
+    pub fn record(&mut self) -> Result<()> {
+        let rows = std::mem::take(&mut self.rows);
+        for row in &rows {
+            let Outcome::Solved(report) = &row.outcome else {
+                continue;
+            };
+            let mut out = Vec::new();
+            self.store(&row.account)?
+                .record_report(row.day, report, &mut out)?;
+            for line in String::from_utf8_lossy(&out).lines() {
+                println!("{}: {line}", row.account);
+            }
+        }
+        self.rows = rows;
+
+        self.stores.values().try_for_each(AnswerStore::save)
+    }
+
+    pub fn print(&self) {
+        const HEADER: [&str; 7] = [
+            "Day", "Input", "Part 1", "Check 1", "Part 2", "Check 2", "Total",
+        ];
+
+        let rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut cells: [String; 7] = Default::default();
+                cells[0] = format!("{} {:0>2}", self.year, row.day);
+                cells[1] = row.account.clone();
+                match &row.outcome {
+                    Outcome::Solved(report) => {
+                        for (part, part_report) in report.parts() {
+                            let column = 2 * part as usize;
+                            cells[column] = match &part_report.answer {
+                                Ok(answer) => answer.clone(),
+                                Err(_) => "error".to_string(),
+                            };
+                        }
+                        cells[6] = format!("{:?}", report.total_time());
+                    }
+                    Outcome::Skipped(_) => cells[2] = "skipped".to_string(),
+                    Outcome::Failed(_) => cells[2] = "failed".to_string(),
+                    Outcome::Panicked(_) => cells[2] = "PANIC".to_string(),
+                    Outcome::TimedOut(_) => cells[2] = "TIMEOUT".to_string(),
+                }
+                for (part, verdict) in &row.verdicts {
+                    cells[2 * *part as usize + 1] = match verdict {
+                        Verdict::Fail { .. } => "FAIL".to_string(),
+                        verdict => verdict.to_string(),
+                    };
+                }
+                cells
+            })
+            .collect();
+        print_table(HEADER, &rows, None);
+
+        for row in &self.rows {
+            let name = format!("Day {:0>2} on {}", row.day, row.account);
+            for (part, verdict) in &row.verdicts {
+                if let Verdict::Fail { .. } = verdict {
+                    println!("{name} part {part}: {verdict}");
+                }
+            }
+            match &row.outcome {
+                Outcome::Solved(report) => {
+                    for (part, part_report) in report.parts() {
+                        if let Err(e) = &part_report.answer {
+                            println!("{name} part {part} failed: {e:#}");
+                        }
+                    }
+                }
+                Outcome::Skipped(reason) => println!("{name} skipped: {reason}"),
+                Outcome::Failed(e) => println!("{name} failed: {e:#}"),
+                Outcome::Panicked(message) => println!("{name} panicked: {message}"),
+                Outcome::TimedOut(timeout) => println!("{name} timed out after {timeout:?}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_inputs, Matrix};
+    use crate::runner::{Outcome, PartReport, Parts, Report};
+    use anyhow::anyhow;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn test_find_inputs() {
+        let dir = std::env::temp_dir().join(format!("adventoffrog-matrix-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("05")).unwrap();
+        for file in [
+            "05.txt",
+            "05.bob.txt",
+            "05_example_1.txt",
+            "05/alice.txt",
+            "05/notes.md",
+            "06.carol.txt",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let accounts: Vec<_> = find_inputs(&dir, 5)
+            .unwrap()
+            .into_iter()
+            .map(|(account, path)| (account, path.strip_prefix(&dir).unwrap().to_owned()))
+            .collect();
+        assert_eq!(
+            accounts,
+            [
+                ("default".to_string(), "05.txt".into()),
+                ("alice".to_string(), "05/alice.txt".into()),
+                ("bob".to_string(), "05.bob.txt".into()),
+            ]
+        );
+        assert!(find_inputs(&dir, 7).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let dir = std::env::temp_dir().join(format!(
+            "adventoffrog-matrix-answers-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("alice")).unwrap();
+        fs::write(dir.join("2023.toml"), "[05]\npart1 = \"35\"\n").unwrap();
+        fs::write(dir.join("alice/2023.toml"), "[05]\npart1 = \"36\"\n").unwrap();
+
+        let solved = |answer: &str| {
+            Outcome::Solved(Report {
+                parse_time: Duration::ZERO,
+                part_1: Some(PartReport {
+                    answer: Ok(answer.to_string()),
+                    time: Some(Duration::ZERO),
+                }),
+                part_2: None,
+                combined_time: None,
+                allocations: Vec::new(),
+            })
+        };
+
+        let mut matrix = Matrix::new(2023, &dir);
+        matrix
+            .push(5, "default", solved("35"), Parts::Both)
+            .unwrap();
+        matrix.push(5, "alice", solved("35"), Parts::Both).unwrap();
+        matrix.push(5, "bob", solved("35"), Parts::Both).unwrap();
+        assert_eq!(matrix.mismatches(), 1);
+
+        // Days that didn't solve fail if an answer was recorded for them
+        for outcome in [
+            Outcome::Failed(anyhow!("Failed to parse seeds")),
+            Outcome::Skipped("Failed to read input".to_string()),
+            Outcome::Panicked("index out of bounds".to_string()),
+            Outcome::TimedOut(Duration::from_secs(1)),
+        ] {
+            let mut matrix = Matrix::new(2023, &dir);
+            matrix.push(5, "alice", outcome, Parts::Both).unwrap();
+            assert_eq!(matrix.mismatches(), 1);
+        }
+        let mut matrix = Matrix::new(2023, &dir);
+        matrix
+            .push(5, "bob", Outcome::Panicked(String::new()), Parts::Both)
+            .unwrap();
+        matrix
+            .push(5, "alice", Outcome::Panicked(String::new()), Parts::Two)
+            .unwrap();
+        assert_eq!(matrix.mismatches(), 0);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::runner::{Outcome, Parts};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Human readable prose or tables
+    #[default]
+    Text,
+    /// A JSON array with one object per part
+    Json,
+    /// CSV with a header line and one row per part
+    Csv,
+}
+
+/// The result of one part of one day in a machine readable form.
+#[derive(Serialize)]
+pub struct Record {
+    pub year: u32,
+    pub day: u8,
+    pub part: u8,
+    /// One of `solved`, `error`, `skipped`, `panic` or `timeout`.
+    pub status: &'static str,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub parse_ns: Option<u64>,
+    pub time_ns: Option<u64>,
+    /// Time for solving both parts together, if the puzzle did so.
+    pub combined_ns: Option<u64>,
+}
+
+impl Record {
+    /// Turns the outcome of a day into one record per selected part.
+    pub fn from_outcome(year: u32, day: u8, outcome: &Outcome, parts: Parts) -> Vec<Record> {
+        let record = |part| Record {
+            year,
+            day,
+            part,
+            status: "skipped",
+            answer: None,
+            error: None,
+            parse_ns: None,
+            time_ns: None,
+            combined_ns: None,
+        };
+
+        let unsolved = |status, error: String| {
+            [1, 2]
+                .into_iter()
+                .filter(|&part| parts.includes(part))
+                .map(|part| Record {
+                    status,
+                    error: Some(error.clone()),
+                    ..record(part)
+                })
+                .collect()
+        };
+
+        match outcome {
+            Outcome::Solved(report) => report
+                .parts()
+                .map(|(part, part_report)| {
+                    let answer = &part_report.answer;
+                    Record {
+                        status: if answer.is_ok() { "solved" } else { "error" },
+                        answer: answer.as_ref().ok().cloned(),
+                        error: answer.as_ref().err().map(|e| format!("{e:#}")),
+                        parse_ns: Some(nanos(report.parse_time)),
+                        time_ns: part_report.time.map(nanos),
+                        combined_ns: report.combined_time.map(nanos),
+                        ..record(part)
+                    }
+                })
+                .collect(),
+            Outcome::Skipped(reason) => unsolved("skipped", reason.clone()),
+            Outcome::Failed(e) => unsolved("error", format!("{e:#}")),
+            Outcome::Panicked(message) => unsolved("panic", message.clone()),
+            Outcome::TimedOut(timeout) => {
+                unsolved("timeout", format!("Timed out after {timeout:?}"))
+            }
+        }
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// Writes the records in a machine readable format. Not meant to be used with [`Format::Text`].
+pub fn write_records(mut out: impl Write, format: Format, records: &[Record]) -> Result<()> {
+    match format {
+        Format::Text => unreachable!("Records have no text representation"),
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut out, records)?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            writeln!(
+                out,
+                "year,day,part,status,answer,error,parse_ns,time_ns,combined_ns"
+            )?;
+            for r in records {
+                let optional =
+                    |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    r.year,
+                    r.day,
+                    r.part,
+                    r.status,
+                    csv_field(r.answer.as_deref().unwrap_or_default()),
+                    csv_field(r.error.as_deref().unwrap_or_default()),
+                    optional(r.parse_ns),
+                    optional(r.time_ns),
+                    optional(r.combined_ns),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Quotes a CSV field if necessary, as described in RFC 4180.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_records, Format, Record};
+    use crate::runner::{Outcome, PartReport, Parts, Report};
+    use anyhow::anyhow;
+    use indoc::indoc;
+    use std::time::Duration;
+
+    fn records() -> Vec<Record> {
+        let report = Report {
+            parse_time: Duration::from_nanos(1500),
+            part_1: Some(PartReport {
+                answer: Ok("35".to_string()),
+                time: Some(Duration::from_nanos(200)),
+            }),
+            part_2: Some(PartReport {
+                answer: Err(anyhow!("Failed to find map for `soil`")),
+                time: Some(Duration::from_nanos(30)),
+            }),
+            combined_time: None,
+            allocations: Vec::new(),
+        };
+
+        let mut records = Vec::new();
+        records.extend(Record::from_outcome(
+            2023,
+            5,
+            &Outcome::Solved(report),
+            Parts::Both,
+        ));
+        records.extend(Record::from_outcome(
+            2023,
+            6,
+            &Outcome::Skipped("No input, \"06.txt\"".to_string()),
+            Parts::Both,
+        ));
+        records
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut out = Vec::new();
+        write_records(&mut out, Format::Csv, &records()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            indoc! {r#"
+                year,day,part,status,answer,error,parse_ns,time_ns,combined_ns
+                2023,5,1,solved,35,,1500,200,
+                2023,5,2,error,,Failed to find map for `soil`,1500,30,
+                2023,6,1,skipped,,"No input, ""06.txt""",,,
+                2023,6,2,skipped,,"No input, ""06.txt""",,,
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let mut out = Vec::new();
+        write_records(&mut out, Format::Json, &records()[..1]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            indoc! {r#"
+                [
+                  {
+                    "year": 2023,
+                    "day": 5,
+                    "part": 1,
+                    "status": "solved",
+                    "answer": "35",
+                    "error": null,
+                    "parse_ns": 1500,
+                    "time_ns": 200,
+                    "combined_ns": null
+                  }
+                ]
+            "#}
+        );
+    }
+}
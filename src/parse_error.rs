@@ -0,0 +1,192 @@
+use std::fmt::{self, Display};
+use std::ops::Range;
+
+/// An error about a specific part of the puzzle input.
+///
+/// Parsers create it from the offending slice of the input with [`WithSpan::with_span`]. Once
+/// the whole input is known, [`locate`] resolves the slice to a line and columns, and the error
+/// gets displayed with that line and a caret underline:
+///
+/// ```text
+/// Failed to parse game ID at line 3, column 6
+///   |
+/// 3 | Game x: 3 blue
+///   |      ^
+/// ```
+#[derive(Debug)]
+pub struct ParseError {
+    message: String,
+    /// Addresses of the offending slice, only compared against the addresses of the input.
+    span: Range<usize>,
+    location: Option<Location>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Location {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based character columns of the offending part of the line.
+    pub columns: Range<usize>,
+    pub text: String,
+}
+
+impl ParseError {
+    /// Creates an error about `span`, which must be a slice of the input to be located.
+    pub fn new(span: &str, message: impl Into<String>) -> Self {
+        let start = span.as_ptr() as usize;
+        ParseError {
+            message: message.into(),
+            span: start..(start + span.len()),
+            location: None,
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// Resolves the span to a location in `input`. Does nothing if the span isn't part of it.
+    pub fn locate(&mut self, input: &str) {
+        let input_start = input.as_ptr() as usize;
+        if self.span.start < input_start || self.span.end > input_start + input.len() {
+            return;
+        }
+        let (start, end) = (self.span.start - input_start, self.span.end - input_start);
+
+        let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[start..].find('\n').map_or(input.len(), |i| start + i);
+        let text = input[line_start..line_end].trim_end_matches('\r');
+        let end = end.min(line_start + text.len());
+
+        let column = input[line_start..start].chars().count() + 1;
+        let width = input[start..end.max(start)].chars().count().max(1);
+        self.location = Some(Location {
+            line: input[..start].matches('\n').count() + 1,
+            columns: column..(column + width),
+            text: text.to_string(),
+        });
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(location) = &self.location else {
+            return f.write_str(&self.message);
+        };
+
+        let line = location.line.to_string();
+        let margin = " ".repeat(line.len());
+        writeln!(
+            f,
+            "{} at line {line}, column {}",
+            self.message, location.columns.start
+        )?;
+        writeln!(f, "{margin} |")?;
+        writeln!(f, "{line} | {}", location.text)?;
+        write!(
+            f,
+            "{margin} | {}{}",
+            " ".repeat(location.columns.start - 1),
+            "^".repeat(location.columns.len())
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Locates a [`ParseError`] in `input`, if `error` is or wraps one.
+pub fn locate(mut error: anyhow::Error, input: &str) -> anyhow::Error {
+    if let Some(parse_error) = error.downcast_mut::<ParseError>() {
+        parse_error.locate(input);
+    }
+    error
+}
+
+/// Turns a failure into a [`ParseError`] about `span`, like [`anyhow::Context`] does for
+/// messages.
+pub trait WithSpan<T> {
+    fn with_span<M: Into<String>>(
+        self,
+        span: &str,
+        message: impl FnOnce() -> M,
+    ) -> Result<T, ParseError>;
+}
+
+impl<T> WithSpan<T> for Option<T> {
+    fn with_span<M: Into<String>>(
+        self,
+        span: &str,
+        message: impl FnOnce() -> M,
+    ) -> Result<T, ParseError> {
+        self.ok_or_else(|| ParseError::new(span, message()))
+    }
+}
+
+impl<T, E: Display> WithSpan<T> for Result<T, E> {
+    /// Appends the original error to the message.
+    fn with_span<M: Into<String>>(
+        self,
+        span: &str,
+        message: impl FnOnce() -> M,
+    ) -> Result<T, ParseError> {
+        self.map_err(|e| ParseError::new(span, format!("{}: {e}", message().into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Location, ParseError, WithSpan};
+    use crate::runner::{self, Parts};
+    use crate::year2023;
+    use indoc::indoc;
+
+    #[test]
+    fn test_locate() {
+        let input = "Game 1: 3 blue\r\nGame x: 4 red\r\n";
+        let id = &input[21..22];
+
+        let mut error = "x".parse::<u32>().with_span(id, || "Bad").unwrap_err();
+        error.locate(input);
+        assert_eq!(
+            error.location(),
+            Some(&Location {
+                line: 2,
+                columns: 6..7,
+                text: "Game x: 4 red".to_string(),
+            })
+        );
+
+        // Empty spans get a single caret, spans past the line end get cut off
+        let mut error = ParseError::new(&input[29..29], "Missing");
+        error.locate(input);
+        assert_eq!(error.location().unwrap().columns, 14..15);
+        let mut error = ParseError::new(&input[24..], "Too long");
+        error.locate(input);
+        assert_eq!(error.location().unwrap().columns, 9..14);
+
+        let mut error = ParseError::new("elsewhere", "Unrelated");
+        error.locate(input);
+        assert_eq!(error.location(), None);
+        assert_eq!(error.to_string(), "Unrelated");
+    }
+
+    #[test]
+    fn test_render() {
+        let input = indoc! {"
+            Game 1: 3 blue, 4 red
+            Game 2: 1 red, 2 green
+            Game 3: 8 green, 6 purple
+        "};
+        let Err(error) = runner::solve::<year2023::day02::Day>(input, Parts::Both) else {
+            panic!("The invalid color wasn't detected");
+        };
+        assert_eq!(
+            format!("{error:#}"),
+            indoc! {"
+                Unrecognized color `purple` at line 3, column 20
+                  |
+                3 | Game 3: 8 green, 6 purple
+                  |                    ^^^^^^"}
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use crate::bench::Bencher;
+use crate::generate::Generator;
+use crate::runner::Solver;
+use itertools::Itertools;
+
+/// A puzzle implementation as seen by the runner.
+pub struct PuzzleEntry {
+    pub year: u32,
+    pub day: u8,
+    /// Path of the implementing type, e.g. `adventoffrog::year2023::day05::Day`.
+    pub name: &'static str,
+    pub solver: Solver,
+    pub bencher: Bencher,
+    pub generator: Option<Generator>,
+}
+
+/// Declares the day modules of a year and registers the puzzles they implement.
+///
+/// Expands to one `pub mod` declaration per day as well as a `PUZZLES` slice, which is picked up
+/// by [`all`]. The year and day of each entry are taken from the [`crate::Puzzle`] impl.
+///
+/// ```ignore
+/// crate::registry::puzzles! {
+///     day01::Day,
+///     day02::Day,
+/// }
+/// ```
+macro_rules! puzzles {
+    ($($module:ident :: $ty:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        pub const PUZZLES: &[crate::registry::PuzzleEntry] = &[
+            $(
+                crate::registry::PuzzleEntry {
+                    year: <$module::$ty as crate::Puzzle>::YEAR,
+                    day: <$module::$ty as crate::Puzzle>::DAY,
+                    name: concat!(module_path!(), "::", stringify!($module), "::", stringify!($ty)),
+                    solver: |input, parts| crate::runner::solve::<$module::$ty>(input, parts),
+                    bencher: |input, options, parts| {
+                        crate::bench::bench::<$module::$ty>(input, options, parts)
+                    },
+                    generator: <$module::$ty as crate::Puzzle>::GENERATOR,
+                },
+            )*
+        ];
+    };
+}
+pub(crate) use puzzles;
+
+// `new` adds to this list when creating the first day of a year.
+const YEARS: &[&[PuzzleEntry]] = &[crate::year2022::PUZZLES, crate::year2023::PUZZLES];
+
+/// All registered puzzles, ordered by year and day.
+pub fn all() -> impl Iterator<Item = &'static PuzzleEntry> {
+    YEARS
+        .iter()
+        .flat_map(|puzzles| puzzles.iter())
+        .sorted_unstable_by_key(|entry| (entry.year, entry.day))
+}
+
+pub fn find(year: u32, day: u8) -> Option<&'static PuzzleEntry> {
+    all().find(|entry| entry.year == year && entry.day == day)
+}
+
+pub fn years() -> impl Iterator<Item = u32> {
+    all().map(|entry| entry.year).dedup()
+}
+
+pub fn days(year: u32) -> impl Iterator<Item = u8> {
+    all()
+        .filter(move |entry| entry.year == year)
+        .map(|entry| entry.day)
+}
+
+pub fn latest_year() -> u32 {
+    years().last().expect("No puzzles registered")
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    #[test]
+    fn test_no_duplicates() {
+        let duplicates: Vec<_> = super::all()
+            .tuple_windows()
+            .filter(|(a, b)| (a.year, a.day) == (b.year, b.day))
+            .map(|(a, b)| format!("{} and {}", a.name, b.name))
+            .collect();
+        assert!(duplicates.is_empty(), "Duplicate puzzles: {duplicates:?}");
+    }
+
+    #[test]
+    fn test_registered_under_own_year() {
+        for entry in super::all() {
+            let module = format!("year{}::day{:0>2}::", entry.year, entry.day);
+            assert!(
+                entry.name.contains(&module),
+                "{} claims to be {} day {}",
+                entry.name,
+                entry.year,
+                entry.day
+            );
+        }
+    }
+}
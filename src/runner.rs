@@ -0,0 +1,495 @@
+use crate::alloc_stats::{self, AllocStats};
+use crate::checked;
+use crate::parse_error;
+use crate::{Answer, Puzzle};
+use anyhow::{bail, Context, Result};
+use std::any::Any;
+use std::hint::black_box;
+use std::panic;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Signature shared by all monomorphized [`solve`] instances, so they can be stored in tables.
+pub type Solver = fn(&str, Parts) -> Result<Report>;
+
+/// Which parts of a puzzle to solve.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Parts {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    #[default]
+    Both,
+}
+
+impl Parts {
+    pub fn includes(self, part: u8) -> bool {
+        match self {
+            Parts::One => part == 1,
+            Parts::Two => part == 2,
+            Parts::Both => true,
+        }
+    }
+}
+
+pub struct Report {
+    pub parse_time: Duration,
+    pub part_1: Option<PartReport>,
+    pub part_2: Option<PartReport>,
+    /// Set if both parts were computed in one go by [`Puzzle::solve_both`]. The individual parts
+    /// have no time of their own then.
+    pub combined_time: Option<Duration>,
+    /// Allocations of each phase, only measured if enabled in [`alloc_stats`].
+    pub allocations: Vec<(&'static str, AllocStats)>,
+}
+
+pub struct PartReport {
+    pub answer: Result<String>,
+    pub time: Option<Duration>,
+}
+
+impl Report {
+    pub fn total_time(&self) -> Duration {
+        self.parse_time
+            + self.parts().filter_map(|(_, part)| part.time).sum()
+            + self.combined_time.unwrap_or_default()
+    }
+
+    /// The parts that were solved, together with their number.
+    pub fn parts(&self) -> impl Iterator<Item = (u8, &PartReport)> {
+        [(1, &self.part_1), (2, &self.part_2)]
+            .into_iter()
+            .filter_map(|(part, report)| Some((part, report.as_ref()?)))
+    }
+
+    pub fn print(&self) {
+        println!("Parsed in {:?}", self.parse_time);
+        for (part, report) in self.parts() {
+            report.print(part);
+        }
+        if let Some(time) = self.combined_time {
+            println!("Both solutions computed in {time:?}");
+        }
+        println!("Total time: {:?}", self.total_time());
+        if !self.allocations.is_empty() {
+            alloc_stats::print(&self.allocations);
+        }
+    }
+}
+
+impl PartReport {
+    fn print(&self, part: u8) {
+        let time = match self.time {
+            Some(time) => format!(" (Computed in {time:?})"),
+            None => String::new(),
+        };
+        match &self.answer {
+            Ok(answer) => println!("Solution {part} is: {answer}{time}"),
+            Err(e) => println!("Solution {part} failed: {e:#}{time}"),
+        }
+    }
+
+    fn answer_cell(&self) -> String {
+        match &self.answer {
+            Ok(answer) => answer.clone(),
+            Err(_) => "error".to_string(),
+        }
+    }
+}
+
+pub fn solve<'a, T: Puzzle<'a>>(input: &'a str, parts: Parts) -> Result<Report> {
+    let mut allocations = Vec::new();
+    let mut track = |phase, stats: Option<AllocStats>| {
+        allocations.extend(stats.map(|stats| (phase, stats)));
+    };
+
+    let start = Instant::now();
+    let (day_xy, stats) = alloc_stats::measure(|| black_box(T::parse(input)));
+    let parse_time = start.elapsed();
+    track("Parse", stats);
+    let day_xy = day_xy.map_err(|e| parse_error::locate(e, input))?;
+
+    // Allocations are reported for each part, so don't let them be solved in one go
+    if parts == Parts::Both && !alloc_stats::is_enabled() {
+        let start = Instant::now();
+        let (solutions, stats) = alloc_stats::measure(|| black_box(day_xy.solve_both()));
+        if let Some((solution_1, solution_2)) = solutions {
+            let time = start.elapsed();
+            track("Both parts", stats);
+            let untimed = |answer| PartReport {
+                answer: attribute::<T>(answer),
+                time: None,
+            };
+
+            return Ok(Report {
+                parse_time,
+                part_1: Some(untimed(solution_1.into_answer())),
+                part_2: Some(untimed(solution_2.into_answer())),
+                combined_time: Some(time),
+                allocations,
+            });
+        }
+    }
+
+    let mut part_1 = None;
+    if parts.includes(1) {
+        let (mut report, stats) = solve_part(|| day_xy.solve_problem_1());
+        report.answer = attribute::<T>(report.answer);
+        track("Part 1", stats);
+        part_1 = Some(report);
+    }
+    let mut part_2 = None;
+    if parts.includes(2) {
+        let (mut report, stats) = solve_part(|| day_xy.solve_problem_2());
+        report.answer = attribute::<T>(report.answer);
+        track("Part 2", stats);
+        part_2 = Some(report);
+    }
+
+    Ok(Report {
+        parse_time,
+        part_1,
+        part_2,
+        combined_time: None,
+        allocations,
+    })
+}
+
+/// Names the puzzle in overflow errors of an answer.
+fn attribute<'a, T: Puzzle<'a>>(answer: Result<String>) -> Result<String> {
+    answer.map_err(|e| checked::attribute(e, T::YEAR, T::DAY))
+}
+
+fn solve_part<S: Answer>(solve: impl FnOnce() -> S) -> (PartReport, Option<AllocStats>) {
+    let start = Instant::now();
+    let (solution, stats) = alloc_stats::measure(|| black_box(solve()));
+    let time = start.elapsed();
+
+    let report = PartReport {
+        answer: solution.into_answer(),
+        time: Some(time),
+    };
+    (report, stats)
+}
+
+/// A set of days given on the command line as a comma separated list of days and day ranges.
+///
+/// ```
+/// # use adventoffrog::runner::DaySelection;
+/// let days: DaySelection = "1-3,7".parse().unwrap();
+/// assert_eq!(days.iter().collect::<Vec<_>>(), [1, 2, 3, 7]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DaySelection {
+    days: Vec<u8>,
+}
+
+impl DaySelection {
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        self.days.iter().copied()
+    }
+}
+
+impl FromIterator<u8> for DaySelection {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut days: Vec<_> = iter.into_iter().collect();
+        days.sort_unstable();
+        days.dedup();
+        DaySelection { days }
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut days = Vec::new();
+        for part in s.split(',') {
+            let parse_day = |day: &str| -> Result<u8> {
+                let day = day
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Failed to parse day: `{day}`"))?;
+                if !crate::DAYS.contains(&day) {
+                    bail!("Day {day} is out of range");
+                }
+                Ok(day)
+            };
+
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (parse_day(start)?, parse_day(end)?);
+                    if start > end {
+                        bail!("Invalid day range: `{part}`");
+                    }
+                    days.extend(start..=end);
+                }
+                None => days.push(parse_day(part)?),
+            }
+        }
+
+        Ok(days.into_iter().collect())
+    }
+}
+
+/// The outcome of running a single day as part of a multi-day run.
+pub enum Outcome {
+    Solved(Report),
+    Skipped(String),
+    Failed(anyhow::Error),
+    /// The solver panicked with the contained message.
+    Panicked(String),
+    /// The solver didn't finish within the contained time.
+    TimedOut(Duration),
+}
+
+impl From<Result<Report>> for Outcome {
+    fn from(result: Result<Report>) -> Self {
+        match result {
+            Ok(report) => Outcome::Solved(report),
+            Err(e) => Outcome::Failed(e),
+        }
+    }
+}
+
+/// Runs `solver` on a separate thread, so a panic or an endless loop only affects this one day.
+///
+/// A solver that exceeds the timeout can't be stopped. Its thread keeps running in the background
+/// until the process exits, which may skew the timings of days run after it.
+pub fn solve_isolated(
+    solver: Solver,
+    input: String,
+    parts: Parts,
+    timeout: Option<Duration>,
+) -> Outcome {
+    let (sender, receiver) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name("solver".to_string())
+        .spawn(move || {
+            let result = panic::catch_unwind(|| solver(&input, parts));
+            // The receiver is gone if we timed out, in which case nobody is interested anymore.
+            let _ = sender.send(result);
+        });
+    if let Err(e) = spawned {
+        return Outcome::Failed(anyhow::Error::new(e).context("Failed to spawn solver thread"));
+    }
+
+    let result = match timeout {
+        Some(timeout) => match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(_) => return Outcome::TimedOut(timeout),
+        },
+        None => receiver
+            .recv()
+            .expect("Solver thread exited without a result"),
+    };
+
+    match result {
+        Ok(result) => result.into(),
+        Err(payload) => Outcome::Panicked(panic_message(payload.as_ref())),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+pub struct Summary {
+    year: u32,
+    rows: Vec<(u8, Outcome)>,
+}
+
+impl Summary {
+    pub fn new(year: u32) -> Self {
+        Summary {
+            year,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, day: u8, outcome: Outcome) {
+        self.rows.push((day, outcome));
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = (u8, &Outcome)> {
+        self.rows.iter().map(|(day, outcome)| (*day, outcome))
+    }
+
+    pub fn print(&self) {
+        const HEADER: [&str; 8] = [
+            "Day", "Parse", "Part 1", "Time 1", "Part 2", "Time 2", "Both", "Total",
+        ];
+
+        let mut table: Vec<[String; 8]> = Vec::new();
+        let mut total_parse = Duration::ZERO;
+        let mut total_1 = Duration::ZERO;
+        let mut total_2 = Duration::ZERO;
+        let mut total_both = Duration::ZERO;
+
+        let optional = |time: Option<Duration>| time.map(|t| format!("{t:?}")).unwrap_or_default();
+
+        for (day, outcome) in &self.rows {
+            let day = format!("{} {day:0>2}", self.year);
+            table.push(match outcome {
+                Outcome::Solved(report) => {
+                    let time_1 = report.part_1.as_ref().and_then(|part| part.time);
+                    let time_2 = report.part_2.as_ref().and_then(|part| part.time);
+                    total_parse += report.parse_time;
+                    total_1 += time_1.unwrap_or_default();
+                    total_2 += time_2.unwrap_or_default();
+                    total_both += report.combined_time.unwrap_or_default();
+
+                    let answer = |part: &Option<PartReport>| {
+                        part.as_ref()
+                            .map(PartReport::answer_cell)
+                            .unwrap_or_default()
+                    };
+                    [
+                        day,
+                        format!("{:?}", report.parse_time),
+                        answer(&report.part_1),
+                        optional(time_1),
+                        answer(&report.part_2),
+                        optional(time_2),
+                        optional(report.combined_time),
+                        format!("{:?}", report.total_time()),
+                    ]
+                }
+                Outcome::Skipped(_) => status_row(day, "skipped"),
+                Outcome::Failed(_) => status_row(day, "failed"),
+                Outcome::Panicked(_) => status_row(day, "PANIC"),
+                Outcome::TimedOut(_) => status_row(day, "TIMEOUT"),
+            });
+        }
+
+        let total = [
+            "Total".to_string(),
+            format!("{total_parse:?}"),
+            String::new(),
+            format!("{total_1:?}"),
+            String::new(),
+            format!("{total_2:?}"),
+            format!("{total_both:?}"),
+            format!("{:?}", total_parse + total_1 + total_2 + total_both),
+        ];
+        print_table(HEADER, &table, Some(&total));
+
+        for (day, outcome) in &self.rows {
+            match outcome {
+                Outcome::Solved(report) => {
+                    for (part, part_report) in report.parts() {
+                        if let Err(e) = &part_report.answer {
+                            println!("Day {day:0>2} part {part} failed: {e:#}");
+                        }
+                    }
+                }
+                Outcome::Skipped(reason) => println!("Day {day:0>2} skipped: {reason}"),
+                Outcome::Failed(e) => println!("Day {day:0>2} failed: {e:#}"),
+                Outcome::Panicked(message) => println!("Day {day:0>2} panicked: {message}"),
+                Outcome::TimedOut(timeout) => {
+                    println!("Day {day:0>2} timed out after {timeout:?}")
+                }
+            }
+        }
+    }
+}
+
+/// Prints an aligned table. The first column is left aligned, all others are right aligned.
+///
+/// The optional footer is printed below the rows, separated by a line, e.g. for totals.
+pub fn print_table<const N: usize>(
+    header: [&str; N],
+    rows: &[[String; N]],
+    footer: Option<&[String; N]>,
+) {
+    let mut widths = header.map(|h| h.chars().count());
+    for row in rows.iter().chain(footer) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |row: &[&str; N]| {
+        let line = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, width))| match i {
+                0 => format!("{cell:<width$}"),
+                _ => format!("{cell:>width$}"),
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+        println!("{}", line.trim_end());
+    };
+    let separator = widths.map(|width| "-".repeat(width)).join("-+-");
+
+    print_row(&header);
+    println!("{separator}");
+    for row in rows {
+        print_row(&row.each_ref().map(String::as_str));
+    }
+    if let Some(footer) = footer {
+        println!("{separator}");
+        print_row(&footer.each_ref().map(String::as_str));
+    }
+}
+
+fn status_row(day: String, status: &str) -> [String; 8] {
+    let mut row: [String; 8] = Default::default();
+    row[0] = day;
+    row[1] = status.to_string();
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve_isolated, DaySelection, Outcome, Parts};
+    use std::time::Duration;
+
+    #[test]
+    fn test_day_selection() {
+        let days: DaySelection = "1-5,7, 3".parse().unwrap();
+        assert_eq!(days.iter().collect::<Vec<_>>(), [1, 2, 3, 4, 5, 7]);
+
+        assert!("5-3".parse::<DaySelection>().is_err());
+        assert!("0".parse::<DaySelection>().is_err());
+        assert!("1,x".parse::<DaySelection>().is_err());
+    }
+
+    #[test]
+    fn test_isolation() {
+        let outcome = solve_isolated(|_, _| panic!("Oh no"), String::new(), Parts::Both, None);
+        assert!(matches!(outcome, Outcome::Panicked(message) if message == "Oh no"));
+
+        let outcome = solve_isolated(
+            |_, _| loop {
+                std::thread::park();
+            },
+            String::new(),
+            Parts::Both,
+            Some(Duration::from_millis(10)),
+        );
+        assert!(matches!(outcome, Outcome::TimedOut(_)));
+
+        let outcome = solve_isolated(
+            |input, parts| super::solve::<crate::year2023::day06::Day>(input, parts),
+            "Time: 7\nDistance: 9\n".to_string(),
+            Parts::One,
+            Some(Duration::from_secs(10)),
+        );
+        let Outcome::Solved(report) = outcome else {
+            panic!("Day 6 wasn't solved");
+        };
+        assert_eq!(report.part_1.unwrap().answer.unwrap(), "4");
+    }
+}
@@ -0,0 +1,372 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+const DAY_TEMPLATE: &str = r#"use crate::Puzzle;
+use anyhow::{bail, Result};
+
+pub struct Day<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Puzzle<'a> for Day<'a> {
+    const YEAR: u32 = {year};
+    const DAY: u8 = {day};
+
+    type Sol1Type = Result<u64>;
+    type Sol2Type = Self::Sol1Type;
+
+    fn parse(input: &'a str) -> Result<Self> {
+        let lines = input.lines().collect();
+        Ok(Self { lines })
+    }
+
+    fn solve_problem_1(&self) -> Self::Sol1Type {
+        bail!("Part 1 is not implemented, {} lines left unsolved", self.lines.len())
+    }
+
+    fn solve_problem_2(&self) -> Self::Sol2Type {
+        bail!("Part 2 is not implemented, {} lines left unsolved", self.lines.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::examples;
+    use crate::Puzzle;
+    use std::path::Path;
+
+    /// The answers declared in the headers of the examples are checked by
+    /// `examples::tests::test_examples`, this only makes sure the examples parse.
+    #[test]
+    fn test_parse_examples() {
+        for example in examples::find(Path::new("input/{year}"), {day}).unwrap() {
+            super::Day::parse(&example.input).unwrap();
+        }
+    }
+}
+"#;
+
+const FUZZ_TARGET_TEMPLATE: &str = r#"#![no_main]
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| adventoffrog_fuzz::run({year}, {day}, data));
+"#;
+
+const FUZZ_BIN_TEMPLATE: &str = r#"
+[[bin]]
+name = "{name}"
+path = "fuzz_targets/{name}.rs"
+test = false
+doc = false
+bench = false
+"#;
+
+/// Creates the module for a new day, registers it and creates empty input files for it. If the
+/// crate has a `fuzz` directory next to `src_path`, a fuzz target gets added as well.
+///
+/// Existing files are never overwritten, only the registration lists get extended.
+pub fn new_day(src_path: &Path, input_path: &Path, year: u32, day: u8) -> Result<()> {
+    let module = format!("day{day:0>2}");
+    let year_dir = src_path.join(format!("year{year}"));
+    let day_file = year_dir.join(format!("{module}.rs"));
+    if day_file.exists() {
+        bail!("{day_file:?} already exists");
+    }
+
+    let mod_file = year_dir.join("mod.rs");
+    if mod_file.exists() {
+        update_file(&mod_file, |content| add_puzzle(content, &module))?;
+    } else {
+        fs::create_dir_all(&year_dir).with_context(|| format!("Failed to create {year_dir:?}"))?;
+        write_new_file(
+            &mod_file,
+            &add_puzzle("crate::registry::puzzles! {\n}\n", &module)?,
+        )?;
+        update_file(&src_path.join("lib.rs"), |content| {
+            add_year_module(content, year)
+        })?;
+        update_file(&src_path.join("registry.rs"), |content| {
+            add_year_to_registry(content, year)
+        })?;
+    }
+
+    let day_source = DAY_TEMPLATE
+        .replace("{year}", &year.to_string())
+        .replace("{day}", &day.to_string());
+    write_new_file(&day_file, &day_source)?;
+
+    let fuzz_dir = src_path.parent().unwrap_or(Path::new("")).join("fuzz");
+    if fuzz_dir.join("Cargo.toml").exists() {
+        add_fuzz_target(&fuzz_dir, year, day)?;
+    }
+
+    let input_dir = input_path.join(year.to_string());
+    fs::create_dir_all(&input_dir).with_context(|| format!("Failed to create {input_dir:?}"))?;
+    for name in [format!("{day:0>2}.txt"), format!("{day:0>2}_example_1.txt")] {
+        let path = input_dir.join(name);
+        if path.exists() {
+            println!("Skipped {path:?}, it already exists");
+        } else {
+            write_new_file(&path, "")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a fuzz target for the day and declares it in the fuzz crate's manifest.
+fn add_fuzz_target(fuzz_dir: &Path, year: u32, day: u8) -> Result<()> {
+    let name = format!("year{year}_day{day:0>2}");
+    let target = fuzz_dir.join("fuzz_targets").join(format!("{name}.rs"));
+    if target.exists() {
+        println!("Skipped {target:?}, it already exists");
+        return Ok(());
+    }
+
+    let fill = |template: &str| {
+        template
+            .replace("{name}", &name)
+            .replace("{year}", &year.to_string())
+            .replace("{day}", &day.to_string())
+    };
+    write_new_file(&target, &fill(FUZZ_TARGET_TEMPLATE))?;
+    update_file(&fuzz_dir.join("Cargo.toml"), |content| {
+        Ok(format!("{content}{}", fill(FUZZ_BIN_TEMPLATE)))
+    })
+}
+
+fn write_new_file(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("Failed to write {path:?}"))?;
+    println!("Created {path:?}");
+    Ok(())
+}
+
+fn update_file(path: &Path, update: impl FnOnce(&str) -> Result<String>) -> Result<()> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {path:?}"))?;
+    let content = update(&content).with_context(|| format!("Failed to update {path:?}"))?;
+    fs::write(path, content).with_context(|| format!("Failed to write {path:?}"))?;
+    println!("Updated {path:?}");
+    Ok(())
+}
+
+/// Adds `<module>::Day` to the `puzzles!` invocation of a year's `mod.rs`, keeping it sorted.
+fn add_puzzle(content: &str, module: &str) -> Result<String> {
+    let (before, rest) = content
+        .split_once("puzzles! {")
+        .context("Failed to find `puzzles!` invocation")?;
+    let (list, after) = rest
+        .split_once('}')
+        .context("Failed to find end of `puzzles!` invocation")?;
+
+    let mut entries: Vec<_> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect();
+    if entries
+        .iter()
+        .any(|entry| entry.split("::").next() == Some(module))
+    {
+        bail!("`{module}` is already registered");
+    }
+    entries.push(format!("{module}::Day"));
+    entries.sort_unstable();
+
+    let list: String = entries.iter().map(|e| format!("    {e},\n")).collect();
+    Ok(format!("{before}puzzles! {{\n{list}}}{after}"))
+}
+
+/// Adds `pub mod year<year>;` to `lib.rs`, next to the other year modules.
+fn add_year_module(content: &str, year: u32) -> Result<String> {
+    let year_modules: Vec<_> = content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let year = line.strip_prefix("pub mod year")?.strip_suffix(';')?;
+            Some((i, year.parse::<u32>().ok()?))
+        })
+        .collect();
+    let Some(&(first, _)) = year_modules.first() else {
+        bail!("Failed to find the existing year modules");
+    };
+    let insert_at = year_modules
+        .iter()
+        .rfind(|&&(_, existing)| existing < year)
+        .map_or(first, |&(i, _)| i + 1);
+
+    let mut lines: Vec<_> = content.lines().collect();
+    let module = format!("pub mod year{year};");
+    lines.insert(insert_at, &module);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds the `PUZZLES` of a year to the `YEARS` list in `registry.rs`.
+fn add_year_to_registry(content: &str, year: u32) -> Result<String> {
+    const START: &str = "const YEARS: &[&[PuzzleEntry]] = &[";
+
+    let (before, rest) = content
+        .split_once(START)
+        .context("Failed to find list of years")?;
+    let (list, after) = rest
+        .split_once("];")
+        .context("Failed to find end of list of years")?;
+
+    let mut entries: Vec<_> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect();
+    entries.push(format!("crate::year{year}::PUZZLES"));
+    entries.sort_unstable();
+    entries.dedup();
+
+    // Mimic rustfmt, which puts the list on a single line if it fits.
+    let single_line = format!("{START}{}];", entries.join(", "));
+    let list = if single_line.len() <= 100 {
+        single_line
+    } else {
+        let list: String = entries.iter().map(|e| format!("    {e},\n")).collect();
+        format!("{START}\n{list}];")
+    };
+    Ok(format!("{before}{list}{after}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use std::fs;
+
+    #[test]
+    fn test_add_puzzle() {
+        let content = indoc! {"
+            crate::registry::puzzles! {
+                day01::Day01,
+                day03::Day,
+            }
+        "};
+
+        assert_eq!(
+            super::add_puzzle(content, "day02").unwrap(),
+            indoc! {"
+                crate::registry::puzzles! {
+                    day01::Day01,
+                    day02::Day,
+                    day03::Day,
+                }
+            "}
+        );
+        assert!(super::add_puzzle(content, "day01").is_err());
+    }
+
+    #[test]
+    fn test_add_year() {
+        let lib = indoc! {"
+            pub mod util;
+            pub mod year2022;
+            pub mod year2023;
+
+            pub const X: u8 = 1;
+        "};
+        assert_eq!(
+            super::add_year_module(lib, 2021).unwrap(),
+            lib.replace("pub mod year2022;", "pub mod year2021;\npub mod year2022;")
+        );
+        assert_eq!(
+            super::add_year_module(lib, 2024).unwrap(),
+            lib.replace("pub mod year2023;", "pub mod year2023;\npub mod year2024;")
+        );
+
+        let registry = indoc! {"
+            // Comment
+            const YEARS: &[&[PuzzleEntry]] = &[crate::year2022::PUZZLES, crate::year2023::PUZZLES];
+
+            fn foo() {}
+        "};
+        assert_eq!(
+            super::add_year_to_registry(registry, 2024).unwrap(),
+            indoc! {"
+                // Comment
+                const YEARS: &[&[PuzzleEntry]] = &[
+                    crate::year2022::PUZZLES,
+                    crate::year2023::PUZZLES,
+                    crate::year2024::PUZZLES,
+                ];
+
+                fn foo() {}
+            "}
+        );
+    }
+
+    #[test]
+    fn test_new_day() {
+        let dir =
+            std::env::temp_dir().join(format!("adventoffrog-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let (src, input, fuzz) = (dir.join("src"), dir.join("input"), dir.join("fuzz"));
+        fs::create_dir_all(src.join("year2023")).unwrap();
+        fs::create_dir_all(fuzz.join("fuzz_targets")).unwrap();
+        fs::write(src.join("lib.rs"), "pub mod year2023;\n").unwrap();
+        fs::write(
+            src.join("registry.rs"),
+            "const YEARS: &[&[PuzzleEntry]] = &[crate::year2023::PUZZLES];\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("year2023/mod.rs"),
+            "crate::registry::puzzles! {\n    day01::Day,\n}\n",
+        )
+        .unwrap();
+        fs::write(fuzz.join("Cargo.toml"), "[package]\n").unwrap();
+        fs::create_dir_all(input.join("2024")).unwrap();
+        fs::write(input.join("2024/05.txt"), "my input\n").unwrap();
+
+        // A day of an existing year only extends its `mod.rs`
+        super::new_day(&src, &input, 2023, 2).unwrap();
+        assert_eq!(
+            fs::read_to_string(src.join("year2023/mod.rs")).unwrap(),
+            "crate::registry::puzzles! {\n    day01::Day,\n    day02::Day,\n}\n"
+        );
+        let day = fs::read_to_string(src.join("year2023/day02.rs")).unwrap();
+        assert!(day.contains("const YEAR: u32 = 2023;\n    const DAY: u8 = 2;"));
+        assert!(day.contains(r#"Path::new("input/2023"), 2)"#));
+
+        // A day of a new year also registers the year, and keeps existing inputs
+        super::new_day(&src, &input, 2024, 5).unwrap();
+        assert_eq!(
+            fs::read_to_string(src.join("year2024/mod.rs")).unwrap(),
+            "crate::registry::puzzles! {\n    day05::Day,\n}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(src.join("lib.rs")).unwrap(),
+            "pub mod year2023;\npub mod year2024;\n"
+        );
+        assert_eq!(
+            fs::read_to_string(src.join("registry.rs")).unwrap(),
+            "const YEARS: &[&[PuzzleEntry]] = &[crate::year2023::PUZZLES, crate::year2024::PUZZLES];\n"
+        );
+        assert_eq!(
+            fs::read_to_string(input.join("2024/05.txt")).unwrap(),
+            "my input\n"
+        );
+        for file in [
+            "2023/02.txt",
+            "2023/02_example_1.txt",
+            "2024/05_example_1.txt",
+        ] {
+            assert_eq!(fs::read_to_string(input.join(file)).unwrap(), "");
+        }
+
+        let manifest = fs::read_to_string(fuzz.join("Cargo.toml")).unwrap();
+        for name in ["year2023_day02", "year2024_day05"] {
+            assert!(manifest.contains(&format!(
+                "[[bin]]\nname = \"{name}\"\npath = \"fuzz_targets/{name}.rs\"\n"
+            )));
+            assert!(fuzz.join(format!("fuzz_targets/{name}.rs")).is_file());
+        }
+
+        assert!(super::new_day(&src, &input, 2023, 2).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
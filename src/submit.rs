@@ -0,0 +1,362 @@
+use crate::client::Client;
+use crate::description::strip_tags;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// What the website thought of a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feedback {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// The answer wasn't checked because the last submission was too recent.
+    RateLimited,
+    /// The part was already solved, or isn't unlocked yet.
+    WrongLevel,
+    /// The response couldn't be understood.
+    Unknown,
+}
+
+impl Feedback {
+    fn is_wrong(self) -> bool {
+        matches!(self, Feedback::Wrong | Feedback::TooHigh | Feedback::TooLow)
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Feedback::Correct => "right",
+            Feedback::Wrong => "wrong",
+            Feedback::TooHigh => "too high",
+            Feedback::TooLow => "too low",
+            Feedback::RateLimited => "rate limited",
+            Feedback::WrongLevel => "wrong level",
+            Feedback::Unknown => "unknown",
+        })
+    }
+}
+
+pub struct Response {
+    pub feedback: Feedback,
+    /// How long to wait before submitting again, if the website said so.
+    pub wait: Option<Duration>,
+    /// The message of the website as plain text.
+    pub message: String,
+}
+
+impl Response {
+    /// Parses the HTML page returned after submitting an answer.
+    pub fn parse(html: &str) -> Self {
+        let article = html
+            .split_once("<article>")
+            .and_then(|(_, rest)| rest.split_once("</article>"))
+            .map_or(html, |(article, _)| article);
+        let text = strip_tags(article);
+        let message = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let feedback = if message.contains("That's the right answer") {
+            Feedback::Correct
+        } else if message.contains("You gave an answer too recently") {
+            Feedback::RateLimited
+        } else if message.contains("You don't seem to be solving the right level") {
+            Feedback::WrongLevel
+        } else if message.contains("That's not the right answer") {
+            if message.contains("your answer is too high") {
+                Feedback::TooHigh
+            } else if message.contains("your answer is too low") {
+                Feedback::TooLow
+            } else {
+                Feedback::Wrong
+            }
+        } else {
+            Feedback::Unknown
+        };
+
+        Response {
+            feedback,
+            wait: parse_wait(&message),
+            message,
+        }
+    }
+}
+
+/// Finds wait times like `You have 1m 39s left to wait` or `please wait 5 minutes`.
+fn parse_wait(message: &str) -> Option<Duration> {
+    if let Some((before, _)) = message.split_once(" left to wait") {
+        let time = before.rsplit_once("You have ")?.1;
+        let mut seconds = 0;
+        for component in time.split_whitespace() {
+            let (value, factor) = match component.strip_suffix('m') {
+                Some(minutes) => (minutes, 60),
+                None => (component.strip_suffix('s')?, 1),
+            };
+            seconds += value.parse::<u64>().ok()? * factor;
+        }
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let message = message.to_lowercase();
+    let (_, after) = message.split_once("please wait ")?;
+    let mut words = after.split_whitespace();
+    let amount = match words.next()? {
+        "one" => 1,
+        n => n.parse().ok()?,
+    };
+    words
+        .next()?
+        .starts_with("minute")
+        .then(|| Duration::from_secs(amount * 60))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attempt {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub feedback: Feedback,
+    /// Unix timestamp in seconds.
+    pub submitted_at: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryFile {
+    #[serde(default)]
+    attempts: Vec<Attempt>,
+}
+
+/// All submissions of a single year, stored as `<answers_path>/<year>_submissions.toml`.
+pub struct History {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl History {
+    /// Loads the history of `year`. A missing file is treated as an empty history.
+    pub fn load(answers_path: &Path, year: u32) -> Result<Self> {
+        let path = answers_path.join(format!("{year}_submissions.toml"));
+        let file: HistoryFile = match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content)
+                .with_context(|| format!("Failed to parse submissions: {path:?}"))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HistoryFile::default(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read submissions: {path:?}"))
+            }
+        };
+
+        Ok(History {
+            path,
+            attempts: file.attempts,
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir:?}"))?;
+        }
+        let content = toml::to_string(&HistoryFile {
+            attempts: self.attempts.clone(),
+        })?;
+        fs::write(&self.path, content).with_context(|| format!("Failed to write {:?}", self.path))
+    }
+
+    pub fn attempts(&self, day: u8, part: u8) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| a.day == day && a.part == part)
+    }
+
+    /// Explains why submitting `answer` would be pointless, based on earlier attempts.
+    pub fn refusal(&self, day: u8, part: u8, answer: &str) -> Option<String> {
+        let mut too_low: Option<(i128, &str)> = None;
+        let mut too_high: Option<(i128, &str)> = None;
+        for attempt in self.attempts(day, part) {
+            if attempt.feedback == Feedback::Correct {
+                return Some(format!("Already solved with {}", attempt.answer));
+            }
+            if attempt.feedback.is_wrong() && attempt.answer == answer {
+                return Some(format!("{answer} is known to be {}", attempt.feedback));
+            }
+
+            let Ok(value) = attempt.answer.parse::<i128>() else {
+                continue;
+            };
+            match attempt.feedback {
+                Feedback::TooLow if too_low.is_none_or(|(low, _)| value > low) => {
+                    too_low = Some((value, &attempt.answer));
+                }
+                Feedback::TooHigh if too_high.is_none_or(|(high, _)| value < high) => {
+                    too_high = Some((value, &attempt.answer));
+                }
+                _ => {}
+            }
+        }
+
+        let value = answer.parse::<i128>().ok()?;
+        match (too_low, too_high) {
+            (Some((low, low_answer)), _) if value <= low => {
+                Some(format!("{answer} is too low, {low_answer} already was"))
+            }
+            (_, Some((high, high_answer))) if value >= high => {
+                Some(format!("{answer} is too high, {high_answer} already was"))
+            }
+            _ => None,
+        }
+    }
+
+    fn record(&mut self, day: u8, part: u8, answer: &str, feedback: Feedback) {
+        self.attempts.push(Attempt {
+            day,
+            part,
+            answer: answer.to_string(),
+            feedback,
+            submitted_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        });
+    }
+}
+
+/// Submits `answer` for a part, unless the history shows that it can't be right.
+///
+/// The attempt is added to the history, which gets saved right away.
+pub fn submit(
+    client: &Client,
+    history: &mut History,
+    year: u32,
+    day: u8,
+    part: u8,
+    answer: &str,
+) -> Result<Response> {
+    if let Some(reason) = history.refusal(day, part, answer) {
+        bail!("Refusing to submit: {reason}");
+    }
+
+    let html = client.post_form(
+        &format!("/{year}/day/{day}/answer"),
+        &[("level", &part.to_string()), ("answer", answer)],
+    )?;
+    let response = Response::parse(&html);
+
+    history.record(day, part, answer, response.feedback);
+    history.save()?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{submit, Feedback, History, Response};
+    use crate::client::stub::StubServer;
+    use crate::client::Client;
+    use std::time::Duration;
+
+    const CORRECT: &str = "<main>\n<article><p>That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer to restoring snow operations. <a href=\"/2023/day/5#part2\">[Continue to Part Two]</a></p></article>\n</main>";
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2023/about\">about page</a>.  Please wait one minute before trying again. <a href=\"/2023/day/5\">[Return to Day 5]</a></p></article>";
+    const RATE_LIMITED: &str = "<article><p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 39s left to wait. <a href=\"/2023/day/5\">[Return to Day 5]</a></p></article>";
+
+    fn history() -> History {
+        History {
+            path: Default::default(),
+            attempts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let response = Response::parse(CORRECT);
+        assert_eq!(response.feedback, Feedback::Correct);
+        assert_eq!(response.wait, None);
+        assert!(response
+            .message
+            .starts_with("That's the right answer! You are one gold star closer"));
+
+        let response = Response::parse(TOO_HIGH);
+        assert_eq!(response.feedback, Feedback::TooHigh);
+        assert_eq!(response.wait, Some(Duration::from_secs(60)));
+
+        let response = Response::parse(RATE_LIMITED);
+        assert_eq!(response.feedback, Feedback::RateLimited);
+        assert_eq!(response.wait, Some(Duration::from_secs(99)));
+
+        let wrong_level = "<article><p>You don't seem to be solving the right level.  Did you already complete it?</p></article>";
+        assert_eq!(Response::parse(wrong_level).feedback, Feedback::WrongLevel);
+        assert_eq!(Response::parse("<html>").feedback, Feedback::Unknown);
+    }
+
+    #[test]
+    fn test_refusal() {
+        let mut history = history();
+        history.record(5, 1, "100", Feedback::TooHigh);
+        history.record(5, 1, "50", Feedback::TooLow);
+        history.record(5, 1, "70", Feedback::Wrong);
+        history.record(5, 1, "abc", Feedback::Wrong);
+        history.record(5, 2, "99", Feedback::RateLimited);
+
+        assert_eq!(history.refusal(5, 1, "60"), None);
+        assert_eq!(
+            history.refusal(5, 1, "70").as_deref(),
+            Some("70 is known to be wrong")
+        );
+        assert_eq!(
+            history.refusal(5, 1, "120").as_deref(),
+            Some("120 is too high, 100 already was")
+        );
+        assert_eq!(
+            history.refusal(5, 1, "40").as_deref(),
+            Some("40 is too low, 50 already was")
+        );
+        assert!(history.refusal(5, 1, "abc").is_some());
+        assert_eq!(history.refusal(5, 2, "99"), None);
+        assert_eq!(history.refusal(6, 1, "120"), None);
+
+        history.record(5, 1, "60", Feedback::Correct);
+        assert_eq!(
+            history.refusal(5, 1, "61").as_deref(),
+            Some("Already solved with 60")
+        );
+    }
+
+    #[test]
+    fn test_submit() {
+        let dir = std::env::temp_dir().join(format!("adventoffrog-submit-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let server = StubServer::start(vec![(200, TOO_HIGH), (200, CORRECT)]);
+        let client = Client::new(
+            &server.base_url,
+            Some("abc".into()),
+            dir.join(".last_request"),
+        )
+        .with_min_interval(Duration::ZERO);
+        let mut history = History::load(&dir, 2023).unwrap();
+
+        let response = submit(&client, &mut history, 2023, 5, 2, "46000").unwrap();
+        assert_eq!(response.feedback, Feedback::TooHigh);
+        // Refused locally, so the server never sees it
+        assert!(submit(&client, &mut history, 2023, 5, 2, "50000").is_err());
+        let response = submit(&client, &mut history, 2023, 5, 2, "46").unwrap();
+        assert_eq!(response.feedback, Feedback::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("POST /2023/day/5/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=46000"));
+
+        let history = History::load(&dir, 2023).unwrap();
+        let attempts: Vec<_> = history
+            .attempts(5, 2)
+            .map(|a| (a.answer.as_str(), a.feedback))
+            .collect();
+        assert_eq!(
+            attempts,
+            [("46000", Feedback::TooHigh), ("46", Feedback::Correct)]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-pub(crate) trait SplitEmptyLines {
+pub trait SplitEmptyLines {
     fn split_empty_lines(&self) -> impl Iterator<Item = &str>;
 }
 
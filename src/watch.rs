@@ -0,0 +1,145 @@
+use crate::runner::{self, Outcome, Parts, Solver};
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the watched file is checked for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Detects changes of a file by polling its modification time and size.
+pub struct FileWatcher {
+    path: PathBuf,
+    last: Option<(SystemTime, u64)>,
+}
+
+impl FileWatcher {
+    pub fn new(path: &Path) -> Self {
+        FileWatcher {
+            path: path.to_path_buf(),
+            last: None,
+        }
+    }
+
+    /// Whether the file changed since the last call. Always true on the first call if the file
+    /// exists.
+    pub fn changed(&mut self) -> bool {
+        let current = fs::metadata(&self.path)
+            .and_then(|meta| Ok((meta.modified()?, meta.len())))
+            .ok();
+        if current.is_none() || current == self.last {
+            return false;
+        }
+        self.last = current;
+        true
+    }
+}
+
+/// Describes how the answers changed compared to the previous run.
+pub fn answer_changes(previous: &[(u8, String)], current: &[(u8, String)]) -> Vec<String> {
+    current
+        .iter()
+        .map(|(part, answer)| {
+            match previous
+                .iter()
+                .find(|(previous_part, _)| previous_part == part)
+            {
+                None => format!("Part {part}: {answer}"),
+                Some((_, old)) if old == answer => format!("Part {part}: {answer} (unchanged)"),
+                Some((_, old)) => format!("Part {part}: {old} -> {answer} (changed)"),
+            }
+        })
+        .collect()
+}
+
+/// Re-runs `solver` whenever the file at `path` changes, until the process gets killed.
+///
+/// `load` reads the input, e.g. stripping the header of an example. Panics of the solver are
+/// reported and watching continues.
+pub fn watch(
+    path: &Path,
+    solver: Solver,
+    parts: Parts,
+    load: impl Fn() -> Result<String>,
+) -> Result<()> {
+    let mut watcher = FileWatcher::new(path);
+    let mut previous = Vec::new();
+    let mut runs = 0;
+    loop {
+        if !watcher.changed() {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        runs += 1;
+
+        // Clear the screen and move the cursor to the top left corner
+        print!("\x1b[2J\x1b[H");
+        println!("Watching {path:?}, run {runs}. Press Ctrl+C to stop.\n");
+
+        let input = match load() {
+            Ok(input) => input,
+            Err(e) => {
+                println!("Failed to load the input: {e:#}");
+                continue;
+            }
+        };
+        match runner::solve_isolated(solver, input, parts, None) {
+            Outcome::Solved(report) => {
+                report.print();
+
+                let current: Vec<_> = report
+                    .parts()
+                    .filter_map(|(part, part_report)| {
+                        Some((part, part_report.answer.as_ref().ok()?.clone()))
+                    })
+                    .collect();
+                if runs > 1 {
+                    println!();
+                    for change in answer_changes(&previous, &current) {
+                        println!("{change}");
+                    }
+                }
+                previous = current;
+            }
+            Outcome::Failed(e) => println!("Failed: {e:#}"),
+            Outcome::Panicked(message) => println!("Panicked: {message}"),
+            Outcome::Skipped(reason) => println!("Skipped: {reason}"),
+            Outcome::TimedOut(timeout) => println!("Timed out after {timeout:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{answer_changes, FileWatcher};
+    use std::fs;
+
+    #[test]
+    fn test_file_watcher() {
+        let path = std::env::temp_dir().join(format!("adventoffrog-watch-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut watcher = FileWatcher::new(&path);
+        assert!(!watcher.changed());
+        fs::write(&path, "1").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        // Same modification time granularity, but a different size
+        fs::write(&path, "12").unwrap();
+        assert!(watcher.changed());
+        fs::remove_file(&path).unwrap();
+        assert!(!watcher.changed());
+    }
+
+    #[test]
+    fn test_answer_changes() {
+        let previous = [(1, "35".to_string()), (2, "46".to_string())];
+        let current = [(1, "35".to_string()), (2, "47".to_string())];
+        assert_eq!(
+            answer_changes(&previous, &current),
+            ["Part 1: 35 (unchanged)", "Part 2: 46 -> 47 (changed)"]
+        );
+        assert_eq!(answer_changes(&[], &current[..1]), ["Part 1: 35"]);
+    }
+}
@@ -1,10 +1,15 @@
 use std::fmt::Debug;
+use std::fmt::Write;
 use std::str::FromStr;
 
+use crate::checked;
+use crate::generate::{Generator, Rng};
+use crate::parse_error::WithSpan;
 use crate::Puzzle;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use arrayvec::ArrayVec;
+use itertools::Itertools;
 
 pub struct Day01 {
     elfs: Vec<Elf>,
@@ -27,19 +32,25 @@ impl FromStr for Food {
         Ok(Food {
             calories: s
                 .parse()
-                .with_context(|| format!("Failed to read calory count from: `{s}`"))?,
+                .with_span(s, || "Failed to read calory count")?,
         })
     }
 }
 
-fn sum_of_calories_per_elf(elfs: &[Elf]) -> impl Iterator<Item = u64> + '_ {
-    elfs.iter()
-        .map(|elf| elf.inventory.iter().map(|food| food.calories).sum())
+fn sum_of_calories_per_elf(elfs: &[Elf]) -> impl Iterator<Item = Result<u64>> + '_ {
+    elfs.iter().map(|elf| {
+        let calories = elf.inventory.iter().map(|food| food.calories);
+        checked::sum(calories, "summing calories")
+    })
 }
 
 impl Puzzle<'_> for Day01 {
-    type Sol1Type = Option<u64>;
-    type Sol2Type = u64;
+    const YEAR: u32 = 2022;
+    const DAY: u8 = 1;
+    const GENERATOR: Option<Generator> = Some(generate);
+
+    type Sol1Type = Result<Option<u64>>;
+    type Sol2Type = Result<u64>;
 
     fn parse(input: &str) -> Result<Self> {
         let mut elfs = Vec::new();
@@ -57,7 +68,7 @@ impl Puzzle<'_> for Day01 {
     }
 
     fn solve_problem_1(&self) -> Self::Sol1Type {
-        sum_of_calories_per_elf(&self.elfs).max()
+        sum_of_calories_per_elf(&self.elfs).process_results(|sums| sums.max())
     }
 
     fn solve_problem_2(&self) -> Self::Sol2Type {
@@ -65,10 +76,25 @@ impl Puzzle<'_> for Day01 {
 
         let mut top = ArrayVec::<_, { NUM_TOP_SPOTS + 1 }>::new();
         for calories in sum_of_calories_per_elf(&self.elfs) {
+            let calories = calories?;
             let i = top.partition_point(|&cur| cur > calories);
             top.insert(i, calories);
             top.truncate(NUM_TOP_SPOTS);
         }
-        top.iter().sum()
+        checked::sum(top, "summing top calories")
+    }
+}
+
+/// Generates the inventories of `size` elves.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for elf in 0..size {
+        if elf > 0 {
+            input.push('\n');
+        }
+        for _ in 0..rng.range(1..16) {
+            writeln!(input, "{}", rng.range(1000..60000)).unwrap();
+        }
     }
+    input
 }
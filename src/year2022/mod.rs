@@ -1 +1,3 @@
-pub mod day01;
+crate::registry::puzzles! {
+    day01::Day01,
+}
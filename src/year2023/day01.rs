@@ -1,3 +1,5 @@
+use crate::checked;
+use crate::generate::{Generator, Rng};
 use crate::Puzzle;
 use anyhow::Result;
 use std::str::Chars;
@@ -6,18 +8,8 @@ pub struct Day<'a> {
     lines: Vec<&'a str>,
 }
 
-/// An iterator over proceedingly shorter substrings of a string slice.
-///
-/// ```
-/// let x = "abc";
-///
-/// let mut substrs = Substrings { iter: x.chars() };
-///
-/// assert_eq!(Some("abc"), substrs.next());
-/// assert_eq!(Some("bc"), substrs.next());
-/// assert_eq!(Some("c"), substrs.next());
-/// assert_eq!(None, substrs.next());
-/// ```
+/// An iterator over proceedingly shorter substrings of a string slice, e.g. `"abc"`, `"bc"`
+/// and `"c"` for `"abc"`.
 struct Substrings<'a> {
     iter: Chars<'a>,
 }
@@ -33,18 +25,8 @@ impl<'a> Iterator for Substrings<'a> {
     }
 }
 
-/// An iterator over proceedingly shorter substrings of a string slice, starting form the end.
-///
-/// ```
-/// let x = "abc";
-///
-/// let mut substrs = RSubstrings { iter: x.chars() };
-///
-/// assert_eq!(Some("abc"), substrs.next());
-/// assert_eq!(Some("ab"), substrs.next());
-/// assert_eq!(Some("a"), substrs.next());
-/// assert_eq!(None, substrs.next());
-/// ```
+/// An iterator over proceedingly shorter substrings of a string slice, starting form the end,
+/// e.g. `"abc"`, `"ab"` and `"a"` for `"abc"`.
 struct RSubstrings<'a> {
     iter: Chars<'a>,
 }
@@ -61,7 +43,11 @@ impl<'a> Iterator for RSubstrings<'a> {
 }
 
 impl<'a> Puzzle<'a> for Day<'a> {
-    type Sol1Type = u32;
+    const YEAR: u32 = 2023;
+    const DAY: u8 = 1;
+    const GENERATOR: Option<Generator> = Some(generate);
+
+    type Sol1Type = Result<u32>;
     type Sol2Type = Self::Sol1Type;
 
     fn parse(input: &'a str) -> Result<Self> {
@@ -70,24 +56,22 @@ impl<'a> Puzzle<'a> for Day<'a> {
     }
 
     fn solve_problem_1(&self) -> Self::Sol1Type {
-        self.lines
-            .iter()
-            .map(|line| {
-                let first_digit = line
-                    .bytes()
-                    .find(u8::is_ascii_digit)
-                    .map(|c| c - b'0')
-                    .unwrap_or_default();
-                let last_digit = line
-                    .bytes()
-                    .rev()
-                    .find(u8::is_ascii_digit)
-                    .map(|c| c - b'0')
-                    .unwrap_or_default();
-
-                Self::Sol1Type::from(first_digit * 10 + last_digit)
-            })
-            .sum()
+        let values = self.lines.iter().map(|line| {
+            let first_digit = line
+                .bytes()
+                .find(u8::is_ascii_digit)
+                .map(|c| c - b'0')
+                .unwrap_or_default();
+            let last_digit = line
+                .bytes()
+                .rev()
+                .find(u8::is_ascii_digit)
+                .map(|c| c - b'0')
+                .unwrap_or_default();
+
+            u32::from(first_digit * 10 + last_digit)
+        });
+        checked::sum(values, "summing calibration values")
     }
 
     fn solve_problem_2(&self) -> Self::Sol2Type {
@@ -109,19 +93,70 @@ impl<'a> Puzzle<'a> for Day<'a> {
             }};
         }
 
-        self.lines
-            .iter()
-            .map(|line| {
-                let first_digit = Substrings { iter: line.chars() }
-                    .find_map(|substr| find_digit!(substr, first, starts_with))
-                    .unwrap_or_default();
+        let values = self.lines.iter().map(|line| {
+            let first_digit = Substrings { iter: line.chars() }
+                .find_map(|substr| find_digit!(substr, first, starts_with))
+                .unwrap_or_default();
+
+            let last_digit = RSubstrings { iter: line.chars() }
+                .find_map(|substr| find_digit!(substr, last, ends_with))
+                .unwrap_or_default();
+
+            u32::from(first_digit * 10 + last_digit)
+        });
+        checked::sum(values, "summing calibration values")
+    }
+}
+
+/// Generates `size` lines of letters, digits and spelled out digits. Each line contains at least
+/// one digit.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    let mut input = String::new();
+    for _ in 0..size {
+        let tokens = rng.range(2..8);
+        let digit_at = rng.range(0..tokens);
+        for token in 0..tokens {
+            match rng.range(0..3) {
+                _ if token == digit_at => input.push_str(&rng.range(1..10).to_string()),
+                0 => input.push_str(&rng.range(1..10).to_string()),
+                1 => input.push_str(WORDS[rng.index(WORDS.len())]),
+                _ => {
+                    for _ in 0..rng.range(1..6) {
+                        input.push(char::from(b'a' + rng.range(0..26) as u8));
+                    }
+                }
+            }
+        }
+        input.push('\n');
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RSubstrings, Substrings};
+
+    #[test]
+    fn test_substrings() {
+        let mut substrs = Substrings { iter: "abc".chars() };
+
+        assert_eq!(Some("abc"), substrs.next());
+        assert_eq!(Some("bc"), substrs.next());
+        assert_eq!(Some("c"), substrs.next());
+        assert_eq!(None, substrs.next());
+    }
 
-                let last_digit = RSubstrings { iter: line.chars() }
-                    .find_map(|substr| find_digit!(substr, last, ends_with))
-                    .unwrap_or_default();
+    #[test]
+    fn test_rsubstrings() {
+        let mut substrs = RSubstrings { iter: "abc".chars() };
 
-                Self::Sol2Type::from(first_digit * 10 + last_digit)
-            })
-            .sum()
+        assert_eq!(Some("abc"), substrs.next());
+        assert_eq!(Some("ab"), substrs.next());
+        assert_eq!(Some("a"), substrs.next());
+        assert_eq!(None, substrs.next());
     }
 }
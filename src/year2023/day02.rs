@@ -1,5 +1,9 @@
+use crate::checked;
+use crate::generate::{Generator, Rng};
+use crate::parse_error::{ParseError, WithSpan};
 use crate::Puzzle;
-use anyhow::{bail, Context, Result};
+use anyhow::Result;
+use std::fmt::Write;
 use std::str::FromStr;
 
 pub struct Day {
@@ -35,13 +39,13 @@ impl FromStr for Game {
     fn from_str(s: &str) -> Result<Self> {
         let (id, sets) = s
             .split_once(": ")
-            .with_context(|| format!("Failed to split game: `{s}`"))?;
+            .with_span(s, || "Failed to split game")?;
         let (_, id) = id
             .split_once(' ')
-            .with_context(|| format!("Failed to split game ID: `{id}`"))?;
+            .with_span(id, || "Failed to split game ID")?;
         let id = id
             .parse()
-            .with_context(|| format!("Failed to parse game ID: `{id}`"))?;
+            .with_span(id, || "Failed to parse game ID")?;
 
         let sets = sets.split("; ").map(str::parse).collect::<Result<_>>()?;
 
@@ -58,16 +62,19 @@ impl FromStr for GameSet {
                 .try_fold((0, 0, 0), |(red, green, blue), color| {
                     let (count, color) = color
                         .split_once(' ')
-                        .with_context(|| format!("Failed to split color: `{color}`"))?;
+                        .with_span(color, || "Failed to split color")?;
                     let count = count
                         .parse()
-                        .with_context(|| format!("Failed to parse color count: `{count}`"))?;
+                        .with_span(count, || "Failed to parse color count")?;
 
                     Ok(match color {
                         "red" => (count, green, blue),
                         "green" => (red, count, blue),
                         "blue" => (red, green, count),
-                        _ => bail!("Unrecognized color {}", color),
+                        _ => {
+                            let message = format!("Unrecognized color `{color}`");
+                            return Err(ParseError::new(color, message));
+                        }
                     })
                 })?;
 
@@ -76,7 +83,12 @@ impl FromStr for GameSet {
 }
 
 impl Puzzle<'_> for Day {
-    type Sol1Type = u32;
+    const YEAR: u32 = 2023;
+    const DAY: u8 = 2;
+    const GENERATOR: Option<Generator> = Some(generate);
+
+    // The power of a game is the product of three counts, which easily exceeds `u32`
+    type Sol1Type = Result<u64>;
     type Sol2Type = Self::Sol1Type;
 
     fn parse(input: &str) -> Result<Self> {
@@ -89,24 +101,49 @@ impl Puzzle<'_> for Day {
         const GREEN_LIMIT: u32 = 13;
         const BLUE_LIMIT: u32 = 14;
 
-        self.games
+        let ids = self
+            .games
             .iter()
             .filter(|game| {
                 let (max_red, max_green, max_blue) = game.max_cube_numbers();
 
                 max_red <= RED_LIMIT && max_green <= GREEN_LIMIT && max_blue <= BLUE_LIMIT
             })
-            .map(|game| game.id)
-            .sum()
+            .map(|game| u64::from(game.id));
+        checked::sum(ids, "summing game IDs")
     }
 
     fn solve_problem_2(&self) -> Self::Sol2Type {
-        self.games
-            .iter()
-            .map(|game| {
-                let (max_red, max_green, max_blue) = game.max_cube_numbers();
-                max_red * max_green * max_blue
+        let mut sum = 0;
+        for game in &self.games {
+            let (max_red, max_green, max_blue) = game.max_cube_numbers();
+            let power = checked::product(
+                [max_red, max_green, max_blue].map(u64::from),
+                "multiplying cube counts",
+            )?;
+            sum = checked::add(sum, power, "summing powers")?;
+        }
+        Ok(sum)
+    }
+}
+
+/// Generates `size` games of up to six sets each.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for id in 1..=size {
+        let sets: Vec<_> = (0..rng.range(1..7))
+            .map(|_| {
+                let mut colors = ["red", "green", "blue"];
+                rng.shuffle(&mut colors);
+                let colors = &colors[..rng.index(3) + 1];
+                let cubes: Vec<_> = colors
+                    .iter()
+                    .map(|color| format!("{} {color}", rng.range(1..21)))
+                    .collect();
+                cubes.join(", ")
             })
-            .sum()
+            .collect();
+        writeln!(input, "Game {id}: {}", sets.join("; ")).unwrap();
     }
+    input
 }
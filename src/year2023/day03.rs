@@ -1,3 +1,6 @@
+use crate::checked;
+use crate::generate::{Generator, Rng};
+use crate::parse_error::WithSpan;
 use crate::Puzzle;
 use anyhow::Result;
 use std::ops::Range;
@@ -75,8 +78,12 @@ impl<'a, T: HasRows> AdjacencyCandidates<'a, T> {
 }
 
 impl Puzzle<'_> for Day {
-    type Sol1Type = u64;
-    type Sol2Type = u64;
+    const YEAR: u32 = 2023;
+    const DAY: u8 = 3;
+    const GENERATOR: Option<Generator> = Some(generate);
+
+    type Sol1Type = Result<u64>;
+    type Sol2Type = Self::Sol1Type;
 
     fn parse(input: &str) -> Result<Self> {
         let mut part_nums = Vec::new();
@@ -100,7 +107,13 @@ impl Puzzle<'_> for Day {
                         num = Some(match num {
                             Some(num) => PartNumber {
                                 columns: num.columns.start..(num.columns.end + 1),
-                                value: num.value * 10 + u64::from(c.to_digit(10).unwrap()),
+                                value: num
+                                    .value
+                                    .checked_mul(10)
+                                    .and_then(|value| value.checked_add(c.to_digit(10)?.into()))
+                                    .with_span(&line[num.columns.start..=i], || {
+                                        "Part number too large"
+                                    })?,
                                 ..num
                             },
                             None => PartNumber {
@@ -130,7 +143,8 @@ impl Puzzle<'_> for Day {
     fn solve_problem_1(&self) -> Self::Sol1Type {
         let mut candidates = AdjacencyCandidates::from(&self.symbols[..]);
 
-        self.part_nums
+        let values = self
+            .part_nums
             .iter()
             .filter(|part_num| {
                 candidates
@@ -138,30 +152,58 @@ impl Puzzle<'_> for Day {
                     .iter()
                     .any(|s| part_num.is_adjacent_to(s))
             })
-            .map(|p| p.value)
-            .sum()
+            .map(|p| p.value);
+        checked::sum(values, "summing part numbers")
     }
 
     fn solve_problem_2(&self) -> Self::Sol2Type {
         let mut candidates = AdjacencyCandidates::from(&self.part_nums[..]);
 
-        self.symbols
-            .iter()
-            .filter(|s| s.value == '*')
-            .map(|symbol| {
-                let (count, gear_ratio) = candidates
-                    .get_for(symbol)
-                    .iter()
-                    .filter(|p| p.is_adjacent_to(symbol))
-                    .fold((0, 1), |(count, acc), p| (count + 1, acc * p.value));
+        let mut sum = 0;
+        for symbol in self.symbols.iter().filter(|s| s.value == '*') {
+            let mut adjacent = candidates
+                .get_for(symbol)
+                .iter()
+                .filter(|p| p.is_adjacent_to(symbol));
+            if let (Some(a), Some(b), None) = (adjacent.next(), adjacent.next(), adjacent.next()) {
+                let gear_ratio = checked::mul(a.value, b.value, "multiplying gear ratio")?;
+                sum = checked::add(sum, gear_ratio, "summing gear ratios")?;
+            }
+        }
+        Ok(sum)
+    }
+}
 
-                match count {
-                    2 => gear_ratio,
-                    _ => 0,
+/// Generates a schematic of `size` by `size` characters.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+
+    let mut input = String::new();
+    for _ in 0..size {
+        let mut row = String::new();
+        while row.len() < size {
+            let remaining = size - row.len();
+            if rng.chance(1, 6) {
+                let number = rng.range(1..1000).to_string();
+                row.push_str(&number[..number.len().min(remaining)]);
+                // Numbers in the same row need to be separated
+                if row.len() < size {
+                    row.push(if rng.chance(1, 4) {
+                        char::from(*rng.pick(SYMBOLS))
+                    } else {
+                        '.'
+                    });
                 }
-            })
-            .sum()
+            } else if rng.chance(1, 8) {
+                row.push(char::from(*rng.pick(SYMBOLS)));
+            } else {
+                row.push('.');
+            }
+        }
+        input.push_str(&row);
+        input.push('\n');
     }
+    input
 }
 
 #[cfg(test)]
@@ -187,4 +229,9 @@ mod tests {
 
         assert_eq!(parsed, expected);
     }
+
+    #[test]
+    fn test_number_too_large() {
+        assert!(super::Day::parse("*18446744073709551616\n").is_err());
+    }
 }
@@ -1,5 +1,9 @@
+use crate::checked;
+use crate::generate::{Generator, Rng};
+use crate::parse_error::{ParseError, WithSpan};
 use crate::Puzzle;
-use anyhow::{Context, Result};
+use anyhow::Result;
+use std::fmt::Write;
 use std::str::FromStr;
 
 pub struct Day {
@@ -26,64 +30,128 @@ impl FromStr for Card {
     fn from_str(s: &str) -> Result<Self> {
         let (_, numbers) = s
             .split_once(": ")
-            .with_context(|| format!("Failed to split card: `{s}`"))?;
+            .with_span(s, || "Failed to split card")?;
         let (winners, draws) = numbers
             .split_once(" | ")
-            .with_context(|| format!("Failed to split numbers: `{numbers}`"))?;
+            .with_span(numbers, || "Failed to split numbers")?;
 
         let winners = winners
             .split(' ')
             .filter(|n| !n.is_empty())
-            .map(str::parse)
-            .collect::<Result<_, _>>()
-            .with_context(|| format!("Failed to parse winners: `{winners}`"))?;
+            .map(|n| n.parse().with_span(n, || "Failed to parse winner"))
+            .collect::<Result<_, _>>()?;
         let draws = draws
             .split(' ')
             .filter(|n| !n.is_empty())
-            .map(str::parse)
-            .collect::<Result<_, _>>()
-            .with_context(|| format!("Failed to parse draws: `{draws}`"))?;
+            .map(|n| n.parse().with_span(n, || "Failed to parse draw"))
+            .collect::<Result<_, _>>()?;
 
         Ok(Card { winners, draws })
     }
 }
 
 impl Puzzle<'_> for Day {
-    type Sol1Type = u64;
+    const YEAR: u32 = 2023;
+    const DAY: u8 = 4;
+    const GENERATOR: Option<Generator> = Some(generate);
+
+    type Sol1Type = Result<u64>;
     type Sol2Type = Self::Sol1Type;
 
     fn parse(input: &str) -> Result<Self> {
-        let cards = input.lines().map(str::parse).collect::<Result<_>>()?;
+        let mut cards = Vec::new();
+        // The card reaching furthest down the table, and the last card it wins a copy of
+        let mut furthest: Option<(&str, usize)> = None;
+        for (i, line) in input.lines().enumerate() {
+            let card: Card = line.parse()?;
+            let last_won = i + card.count_matches();
+            if furthest.is_none_or(|(_, last)| last_won > last) {
+                furthest = Some((line, last_won));
+            }
+            cards.push(card);
+        }
+
+        // Cards never make you copy a card past the end of the table
+        if let Some((line, last_won)) = furthest {
+            if last_won >= cards.len() {
+                let message = "Card wins copies of cards past the end of the table";
+                return Err(ParseError::new(line, message).into());
+            }
+        }
+
         Ok(Day { cards })
     }
 
     fn solve_problem_1(&self) -> Self::Sol1Type {
-        self.cards
-            .iter()
-            .map(|card| {
-                let matches = card.count_matches();
-
-                match matches {
-                    0 => 0,
-                    _ => 1 << (matches - 1),
-                }
-            })
-            .sum()
+        points(self.cards.iter().map(Card::count_matches))
     }
 
     fn solve_problem_2(&self) -> Self::Sol2Type {
-        let mut counts = vec![1; self.cards.len()];
+        num_scratchcards(self.cards.iter().map(Card::count_matches))
+    }
 
-        for (i, cur) in self.cards.iter().enumerate() {
-            let matches = cur.count_matches();
-            let cur_copies = counts[i];
-            for count in counts[(i + 1)..(i + 1 + matches)].iter_mut() {
-                *count += cur_copies;
-            }
+    fn solve_both(&self) -> Option<(Self::Sol1Type, Self::Sol2Type)> {
+        let matches: Vec<_> = self.cards.iter().map(Card::count_matches).collect();
+        Some((
+            points(matches.iter().copied()),
+            num_scratchcards(matches.iter().copied()),
+        ))
+    }
+}
+
+fn points(matches: impl Iterator<Item = usize>) -> Result<u64> {
+    let mut sum = 0;
+    for matches in matches {
+        let points = match matches {
+            0 => 0,
+            // At most the number of winning numbers, which fits into a `u32` for any sane input
+            _ => checked::shl(1, (matches - 1) as u32, "doubling points")?,
+        };
+        sum = checked::add(sum, points, "summing points")?;
+    }
+    Ok(sum)
+}
+
+/// The number of copies can double with every card.
+fn num_scratchcards(matches: impl ExactSizeIterator<Item = usize>) -> Result<u64> {
+    let mut counts = vec![1; matches.len()];
+
+    for (i, matches) in matches.enumerate() {
+        let cur_copies = counts[i];
+        for count in counts[(i + 1)..(i + 1 + matches)].iter_mut() {
+            *count = checked::add(*count, cur_copies, "counting copies")?;
         }
+    }
 
-        counts.iter().sum()
+    checked::sum(counts, "summing scratchcards")
+}
+
+/// Generates `size` cards with ten winning numbers and 25 drawn numbers each.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for card in 1..=size {
+        let mut numbers: Vec<_> = (1..100).collect();
+        rng.shuffle(&mut numbers);
+        let (winners, others) = numbers.split_at(10);
+
+        // Cards may not win copies of cards past the end of the table
+        let matches = rng.index((size - card).min(10) + 1);
+        let mut draws = [&winners[..matches], &others[..25 - matches]].concat();
+        rng.shuffle(&mut draws);
+
+        let format = |numbers: &[u64]| {
+            let numbers: Vec<_> = numbers.iter().map(|n| format!("{n:>2}")).collect();
+            numbers.join(" ")
+        };
+        writeln!(
+            input,
+            "Card {card:>3}: {} | {}",
+            format(winners),
+            format(&draws)
+        )
+        .unwrap();
     }
+    input
 }
 
 #[cfg(test)]
@@ -91,10 +159,23 @@ mod tests {
     use crate::Puzzle;
 
     #[test]
-    fn test_example() {
-        let input = std::fs::read_to_string("input/2023/04_example_1.txt").unwrap();
+    fn test_copies_past_end() {
+        assert!(super::Day::parse("Card 1: 41 48 | 41 48\nCard 2: 13 32 | 61 30\n").is_err());
+    }
+
+    #[cfg(feature = "checked-arithmetic")]
+    #[test]
+    fn test_copies_overflow() {
+        // Every card wins a copy of each following card, so the copies double with every card
+        let input: String = (1..=70)
+            .map(|card| {
+                let numbers: Vec<_> = (card + 1..=70).map(|n| n.to_string()).collect();
+                let numbers = numbers.join(" ");
+                format!("Card {card}: {numbers} 99 | {numbers} 98\n")
+            })
+            .collect();
         let day = super::Day::parse(&input).unwrap();
-        assert_eq!(day.solve_problem_1(), 13);
-        assert_eq!(day.solve_problem_2(), 30);
+        let error = day.solve_problem_2().unwrap_err();
+        assert_eq!(error.to_string(), "Overflow in counting copies");
     }
 }
@@ -1,8 +1,11 @@
+use crate::generate::{Generator, Rng};
+use crate::parse_error::{ParseError, WithSpan};
 use crate::util::SplitEmptyLines;
 use crate::Puzzle;
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::ops::Range;
 use std::str::FromStr;
 
@@ -18,6 +21,10 @@ struct Mapping {
 
 impl Mapping {
     fn map(&self, value: Range<u64>, mapped: &mut Vec<Range<u64>>, unmapped: &mut Vec<Range<u64>>) {
+        if value.is_empty() {
+            return;
+        }
+
         let contains_start = self.src.contains(&value.start);
         let contains_end = self.src.contains(&(value.end - 1));
 
@@ -75,25 +82,31 @@ impl FromStr for Mapping {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let (dst_start, s) = s
+        let (dst_start, rest) = s
             .split_once(' ')
-            .with_context(|| format!("Failed to split mapping: `{s}`"))?;
-        let (src_start, length) = s
+            .with_span(s, || "Failed to split mapping")?;
+        let (src_start, length) = rest
             .split_once(' ')
-            .with_context(|| format!("Failed to split mapping: `{s}`"))?;
+            .with_span(rest, || "Failed to split mapping")?;
 
-        let dst_start = dst_start
+        let dst_start: u64 = dst_start
             .parse()
-            .with_context(|| format!("Failed to parse dst_start: `{dst_start}`"))?;
-        let src_start = src_start
+            .with_span(dst_start, || "Failed to parse dst_start")?;
+        let src_start: u64 = src_start
             .parse()
-            .with_context(|| format!("Failed to parse src_start: `{src_start}`"))?;
-        let length: u64 = length
+            .with_span(src_start, || "Failed to parse src_start")?;
+        let length = length
             .parse()
-            .with_context(|| format!("Failed to parse length: `{length}`"))?;
+            .with_span(length, || "Failed to parse length")?;
+
+        // Both ranges need to fit into u64, so the mapped values can't overflow
+        let src_end = src_start
+            .checked_add(length)
+            .filter(|_| dst_start.checked_add(length).is_some())
+            .with_span(s, || "Mapping exceeds the range of u64")?;
 
         Ok(Mapping {
-            src: src_start..(src_start + length),
+            src: src_start..src_end,
             dst: dst_start,
         })
     }
@@ -147,10 +160,19 @@ fn compute_closest_seed_location(
         src_types.push(dst_type);
     }
 
-    Ok(number_ranges.iter().map(|r| r.start).min().unwrap())
+    number_ranges
+        .iter()
+        .filter(|r| !r.is_empty())
+        .map(|r| r.start)
+        .min()
+        .context("No seeds to locate")
 }
 
 impl<'a> Puzzle<'a> for Day<'a> {
+    const YEAR: u32 = 2023;
+    const DAY: u8 = 5;
+    const GENERATOR: Option<Generator> = Some(generate);
+
     type Sol1Type = Result<u64>;
     type Sol2Type = Self::Sol1Type;
 
@@ -161,22 +183,23 @@ impl<'a> Puzzle<'a> for Day<'a> {
         for block in input.split_empty_lines() {
             let (title, values) = block
                 .split_once(':')
-                .with_context(|| format!("Failed to split block: `{block}`"))?;
+                .with_span(block, || "Failed to split block")?;
             if title == "seeds" {
                 seeds = Some(
                     values
                         .split_ascii_whitespace()
-                        .map(str::parse)
+                        .map(|seed| seed.parse().with_span(seed, || "Failed to parse seed"))
                         .collect::<Result<_, _>>()?,
                 )
             } else {
                 let Some((title, "map")) = title.split_once(' ') else {
-                    bail!("Unrecognized title `{title}`");
+                    let message = format!("Unrecognized title `{title}`");
+                    return Err(ParseError::new(title, message).into());
                 };
 
                 let (src_type, dst_type) = title
                     .split_once("-to-")
-                    .with_context(|| format!("Unrecognized map title `{title}`"))?;
+                    .with_span(title, || format!("Unrecognized map title `{title}`"))?;
 
                 let mapping = values
                     .trim()
@@ -185,7 +208,8 @@ impl<'a> Puzzle<'a> for Day<'a> {
                     .collect::<Result<_>>()?;
 
                 if mappings.insert(src_type, (dst_type, mapping)).is_some() {
-                    bail!("Duplicate map for source type `{src_type}`");
+                    let message = format!("Duplicate map for source type `{src_type}`");
+                    return Err(ParseError::new(src_type, message).into());
                 }
             }
         }
@@ -197,7 +221,12 @@ impl<'a> Puzzle<'a> for Day<'a> {
 
     fn solve_problem_1(&self) -> Self::Sol1Type {
         // Turn each seed into a range with length 1, so we can reuse the solution for part 2
-        let seed_ranges = self.seeds.iter().map(|&seed| seed..seed + 1).collect();
+        let seed_ranges = self
+            .seeds
+            .iter()
+            .map(|&seed| Some(seed..seed.checked_add(1)?))
+            .collect::<Option<_>>()
+            .context("Seed exceeds the range of u64")?;
         compute_closest_seed_location(seed_ranges, &self.mappings)
     }
 
@@ -206,23 +235,120 @@ impl<'a> Puzzle<'a> for Day<'a> {
             .seeds
             .iter()
             .tuples()
-            .map(|(&start, &len)| start..(start + len))
-            .collect();
+            .map(|(&start, &len)| Some(start..start.checked_add(len)?))
+            .collect::<Option<_>>()
+            .context("Seed range exceeds the range of u64")?;
         compute_closest_seed_location(seed_ranges, &self.mappings)
     }
 }
 
+/// Generates an almanac with ten seed ranges and `size` mappings in each of the seven maps.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const TYPES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    const MAX: u64 = 1 << 32;
+
+    let mut input = "seeds:".to_string();
+    for _ in 0..10 {
+        let start = rng.range(0..MAX - 1);
+        let length = rng.range(1..(MAX - start).min(1 << 28));
+        write!(input, " {start} {length}").unwrap();
+    }
+    input.push('\n');
+
+    for types in TYPES.windows(2) {
+        write!(input, "\n{}-to-{} map:\n", types[0], types[1]).unwrap();
+
+        // Non-overlapping source ranges between sorted cut points
+        let mut cuts: Vec<_> = (0..2 * size).map(|_| rng.range(0..MAX)).collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+        let mut mappings: Vec<_> = cuts
+            .chunks_exact(2)
+            .map(|range| {
+                let length = range[1] - range[0];
+                (rng.range(0..MAX - length), range[0], length)
+            })
+            .collect();
+        rng.shuffle(&mut mappings);
+        for (dst, src, length) in mappings {
+            writeln!(input, "{dst} {src} {length}").unwrap();
+        }
+    }
+    input
+}
+
 #[cfg(test)]
 mod tests {
+    use super::Day;
     use crate::Puzzle;
     use indoc::indoc;
+    use proptest::prelude::*;
+    use std::fmt::Write;
+
+    /// Maps a single seed to its location, applying the first mapping that contains the value.
+    fn reference_location(day: &Day, seed: u64) -> u64 {
+        let (mut src_type, mut value) = ("seed", seed);
+        while src_type != "location" {
+            let (dst_type, mappings) = &day.mappings[src_type];
+            if let Some(mapping) = mappings.iter().find(|m| m.src.contains(&value)) {
+                value = value - mapping.src.start + mapping.dst;
+            }
+            src_type = dst_type;
+        }
+        value
+    }
 
-    #[test]
-    fn test_example() {
-        let input = std::fs::read_to_string("input/2023/05_example_1.txt").unwrap();
-        let day = super::Day::parse(&input).unwrap();
-        assert_eq!(day.solve_problem_1().unwrap(), 35);
-        assert_eq!(day.solve_problem_2().unwrap(), 46);
+    fn reference(day: &Day, seeds: impl Iterator<Item = u64>) -> Option<u64> {
+        seeds.map(|seed| reference_location(day, seed)).min()
+    }
+
+    /// Almanacs with a chain of up to four maps from seed to location, using small numbers so
+    /// the seed ranges can be enumerated.
+    fn almanac() -> impl Strategy<Value = String> {
+        const TYPES: [&str; 4] = ["seed", "soil", "water", "light"];
+
+        let seeds = prop::collection::vec((0..100u64, 0..20u64), 1..4);
+        let mapping = (0..120u64, 0..100u64, 0..30u64);
+        let maps = prop::collection::vec(prop::collection::vec(mapping, 0..5), 1..=4);
+        (seeds, maps).prop_map(|(seeds, maps)| {
+            let mut input = "seeds:".to_string();
+            for (start, length) in seeds {
+                write!(input, " {start} {length}").unwrap();
+            }
+            input.push('\n');
+
+            for (i, mappings) in maps.iter().enumerate() {
+                let dst_type = TYPES.get(i + 1).filter(|_| i + 1 < maps.len());
+                let dst_type = dst_type.unwrap_or(&"location");
+                write!(input, "\n{}-to-{dst_type} map:\n", TYPES[i]).unwrap();
+                for (dst, src, length) in mappings {
+                    writeln!(input, "{dst} {src} {length}").unwrap();
+                }
+            }
+            input
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_against_reference(input in almanac()) {
+            let day = Day::parse(&input).unwrap();
+
+            let seeds = day.seeds.iter().copied();
+            prop_assert_eq!(day.solve_problem_1().ok(), reference(&day, seeds));
+
+            let seed_ranges = day.seeds.chunks(2).flat_map(|pair| pair[0]..pair[0] + pair[1]);
+            prop_assert_eq!(day.solve_problem_2().ok(), reference(&day, seed_ranges));
+        }
     }
 
     #[test]
@@ -238,7 +364,23 @@ mod tests {
             30 60 10
         "};
 
-        let day = super::Day::parse(INPUT).unwrap();
+        let day = Day::parse(INPUT).unwrap();
         assert_eq!(day.solve_problem_2().unwrap(), 20);
     }
+
+    #[test]
+    fn test_empty_seed_range() {
+        const INPUT: &str = indoc! {"
+            seeds: 0 0 79 14
+
+            seed-to-location map:
+            50 98 2
+            52 50 48
+        "};
+
+        let day = Day::parse(INPUT).unwrap();
+        assert_eq!(day.solve_problem_2().unwrap(), 81);
+        let day = Day::parse("seeds: 0 0\n").unwrap();
+        assert!(day.solve_problem_2().is_err());
+    }
 }
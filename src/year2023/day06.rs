@@ -1,5 +1,9 @@
+use crate::checked;
+use crate::generate::{Generator, Rng};
+use crate::parse_error::WithSpan;
 use crate::Puzzle;
-use anyhow::{Context, Result};
+use anyhow::Result;
+use std::fmt::Write;
 use std::iter;
 
 pub struct Day {
@@ -13,7 +17,7 @@ struct Race {
 }
 
 impl Race {
-    fn num_possible_winning_moves(&self) -> u32 {
+    fn num_possible_winning_moves(&self) -> Result<u64> {
         // The problem can be represented by the inequality (T-t)t > D,
         // where T is the time and D is the distance.
         // Solving for t, first it can be transformed to:
@@ -39,39 +43,51 @@ impl Race {
         // If the square root is NaN, there's no solution even in the real numbers. Needs to be
         // checked separately since the NaN will propagate to both bounds but NaN != NaN.
         if lower_bound.trunc() == upper_bound.trunc() || root.is_nan() {
-            return 0;
+            return Ok(0);
         }
 
         // Convert the result from real numbers to integers.
         // Need to make sure a lower bound of e.g. 4.0 gets bumped to 5, since the inequality
-        // is `> D`, not `>= D`.
-        let lower_bound = lower_bound.floor() as u32 + 1;
-        let upper_bound = upper_bound.ceil() as u32 - 1;
+        // is `> D`, not `>= D`. Stay in floating point until the end, the bounds may be
+        // negative or exceed the integer range for nonsensical races.
+        let lower_bound = lower_bound.floor().max(-1.0) + 1.0;
+        let upper_bound = upper_bound.ceil() - 1.0;
 
-        upper_bound - lower_bound + 1
+        let moves = upper_bound - lower_bound + 1.0;
+        checked::from_f64(moves, "counting winning moves")
     }
 }
 
 impl<'a> Puzzle<'a> for Day {
-    type Sol1Type = u32;
+    const YEAR: u32 = 2023;
+    const DAY: u8 = 6;
+    const GENERATOR: Option<Generator> = Some(generate);
+
+    // A race allows up to its time in winning moves, which may well exceed `u32`
+    type Sol1Type = Result<u64>;
     type Sol2Type = Self::Sol1Type;
 
     fn parse(input: &str) -> Result<Self> {
         let (times, distances) = input
             .split_once('\n')
-            .with_context(|| format!("Failed to split lines: `{input}`"))?;
+            .with_span(input, || "Failed to split lines")?;
         let (_, times) = times
             .split_once(':')
-            .with_context(|| format!("Failed to split times: `{times}`"))?;
+            .with_span(times, || "Failed to split times")?;
         let (_, distances) = distances
             .split_once(':')
-            .with_context(|| format!("Failed to split times: `{distances}`"))?;
+            .with_span(distances, || "Failed to split distances")?;
 
-        let combined_time = times.split_ascii_whitespace().collect::<String>().parse()?;
+        let combined_time = times
+            .split_ascii_whitespace()
+            .collect::<String>()
+            .parse()
+            .with_span(times, || "Failed to parse combined time")?;
         let combined_distance = distances
             .split_ascii_whitespace()
             .collect::<String>()
-            .parse()?;
+            .parse()
+            .with_span(distances, || "Failed to parse combined distance")?;
 
         let combined_race = Race {
             time: combined_time,
@@ -80,11 +96,11 @@ impl<'a> Puzzle<'a> for Day {
 
         let times = times
             .split_ascii_whitespace()
-            .map(str::parse)
+            .map(|time| time.parse().with_span(time, || "Failed to parse time"))
             .collect::<Result<Vec<_>, _>>()?;
         let distances = distances
             .split_ascii_whitespace()
-            .map(str::parse)
+            .map(|distance| distance.parse().with_span(distance, || "Failed to parse distance"))
             .collect::<Result<Vec<_>, _>>()?;
 
         let races = iter::zip(times, distances)
@@ -98,10 +114,12 @@ impl<'a> Puzzle<'a> for Day {
     }
 
     fn solve_problem_1(&self) -> Self::Sol1Type {
-        self.races
-            .iter()
-            .map(Race::num_possible_winning_moves)
-            .product()
+        let mut product = 1;
+        for race in &self.races {
+            let moves = race.num_possible_winning_moves()?;
+            product = checked::mul(product, moves, "multiplying winning moves")?;
+        }
+        Ok(product)
     }
 
     fn solve_problem_2(&self) -> Self::Sol2Type {
@@ -109,17 +127,39 @@ impl<'a> Puzzle<'a> for Day {
     }
 }
 
+/// Generates `size` races, but at most four, so the combined race still fits into the integers.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let races: Vec<_> = (0..size.clamp(1, 4))
+        .map(|_| {
+            let time = rng.range(7..100);
+            // Leave at least one way to win
+            (time, rng.range(0..time * time / 4))
+        })
+        .collect();
+
+    let mut input = "Time:    ".to_string();
+    for (time, _) in &races {
+        write!(input, " {time:>4}").unwrap();
+    }
+    input.push_str("\nDistance:");
+    for (_, distance) in &races {
+        write!(input, " {distance:>4}").unwrap();
+    }
+    input.push('\n');
+    input
+}
+
 #[cfg(test)]
 mod tests {
+    use super::Race;
     use crate::Puzzle;
     use indoc::indoc;
+    use proptest::prelude::*;
 
-    #[test]
-    fn test_example() {
-        let input = std::fs::read_to_string("input/2023/06_example_1.txt").unwrap();
-        let day = super::Day::parse(&input).unwrap();
-        assert_eq!(day.solve_problem_1(), 288);
-        assert_eq!(day.solve_problem_2(), 71503);
+    /// Simulates every possible button press duration.
+    fn reference(race: &Race) -> u64 {
+        let winning = (0..=race.time).filter(|&hold| (race.time - hold) * hold > race.distance);
+        winning.count() as u64
     }
 
     #[test]
@@ -130,6 +170,31 @@ mod tests {
         "};
 
         let day = super::Day::parse(INPUT).unwrap();
-        assert_eq!(day.solve_problem_2(), 55761118);
+        assert_eq!(day.solve_problem_2().unwrap(), 55761118);
+    }
+
+    #[test]
+    fn test_no_winning_moves() {
+        let race = Race {
+            time: 1,
+            distance: 0,
+        };
+        assert_eq!(race.num_possible_winning_moves().unwrap(), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn test_against_reference(time in 0..2000u64, distance in 0..1_100_000u64) {
+            let race = Race { time, distance };
+            prop_assert_eq!(race.num_possible_winning_moves().unwrap(), reference(&race));
+        }
+
+        #[test]
+        fn test_record_exactly_matched(time in 0..2000u64, hold in 0..2000u64) {
+            // Integer roots are where floating point bounds are most likely to be off by one
+            let hold = hold.min(time);
+            let race = Race { time, distance: (time - hold) * hold };
+            prop_assert_eq!(race.num_possible_winning_moves().unwrap(), reference(&race));
+        }
     }
 }
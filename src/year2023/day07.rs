@@ -1,6 +1,11 @@
+use crate::checked;
+use crate::generate::{Generator, Rng};
+use crate::parse_error::{ParseError, WithSpan};
 use crate::Puzzle;
-use anyhow::{bail, Context, Result};
+use anyhow::Result;
 use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::Write;
 use std::iter::zip;
 
 pub struct Day {
@@ -40,57 +45,98 @@ struct Hand {
     cards: Box<[Card; 5]>,
     // Using an array of counters is a good 30% faster overall than a HashMap
     card_counters: Box<[usize; 13]>,
-    bid: u32,
+    bid: u64,
+}
+
+impl HandType {
+    fn from_stats((num_counters, max_counter): (usize, usize)) -> Self {
+        match (num_counters, max_counter) {
+            (0, _) => HandType::FiveOfAKind, // For all J hands
+            (1, _) => HandType::FiveOfAKind,
+            (2, 4) => HandType::FourOfAKind,
+            (2, 3) => HandType::FullHouse,
+            (3, 3) => HandType::ThreeOfAKind,
+            (3, 2) => HandType::TwoPair,
+            (4, _) => HandType::OnePair,
+            (5, _) => HandType::HighCard,
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl Day {
-    fn winnings(
-        &self,
-        card_strength: impl Fn(&Card) -> Card,
-        card_stats: impl Fn(&Hand) -> (usize, usize),
-    ) -> u32 {
-        let mut hands: Vec<_> = self
-            .hands
+    fn typed_hands(&self, card_stats: impl Fn(&Hand) -> (usize, usize)) -> Vec<(&Hand, HandType)> {
+        self.hands
             .iter()
-            .map(|hand| {
-                let (num_counters, max_counter) = card_stats(hand);
-
-                let type_ = match (num_counters, max_counter) {
-                    (0, _) => HandType::FiveOfAKind, // For all J hands
-                    (1, _) => HandType::FiveOfAKind,
-                    (2, 4) => HandType::FourOfAKind,
-                    (2, 3) => HandType::FullHouse,
-                    (3, 3) => HandType::ThreeOfAKind,
-                    (3, 2) => HandType::TwoPair,
-                    (4, _) => HandType::OnePair,
-                    (5, _) => HandType::HighCard,
-                    _ => unreachable!(),
-                };
-
-                (hand, type_)
-            })
-            .collect();
+            .map(|hand| (hand, HandType::from_stats(card_stats(hand))))
+            .collect()
+    }
+}
 
-        hands.sort_unstable_by(|(a, a_type), (b, b_type)| match a_type.cmp(&b_type) {
-            Ordering::Equal => zip(a.cards.iter(), b.cards.iter())
-                .find(|(a_card, b_card)| a_card != b_card)
-                .map(|(a_card, b_card)| card_strength(a_card).cmp(&card_strength(b_card)))
-                .unwrap_or(Ordering::Equal),
-            res => res,
-        });
+fn winnings(
+    hands: &mut [(&Hand, HandType)],
+    card_strength: impl Fn(&Card) -> Card,
+) -> Result<u64> {
+    hands.sort_unstable_by(|(a, a_type), (b, b_type)| match a_type.cmp(b_type) {
+        Ordering::Equal => zip(a.cards.iter(), b.cards.iter())
+            .find(|(a_card, b_card)| a_card != b_card)
+            .map(|(a_card, b_card)| card_strength(a_card).cmp(&card_strength(b_card)))
+            .unwrap_or(Ordering::Equal),
+        res => res,
+    });
 
-        hands
-            .iter()
-            .enumerate()
-            .map(|(rank, (hand, _))| hand.bid * (u32::try_from(rank).unwrap() + 1))
-            .sum()
+    let mut sum = 0;
+    for (rank, (hand, _)) in (1..).zip(hands.iter()) {
+        let winnings = checked::mul(hand.bid, rank, "multiplying bid by rank")?;
+        sum = checked::add(sum, winnings, "summing winnings")?;
+    }
+    Ok(sum)
+}
+
+fn card_strength(card: &Card) -> Card {
+    *card
+}
+
+fn card_stats(hand: &Hand) -> (usize, usize) {
+    let num_counters = hand
+        .card_counters
+        .iter()
+        .filter(|&counter| *counter != 0)
+        .count();
+    let max_counter = hand.card_counters.iter().copied().max().unwrap_or_default();
+
+    (num_counters, max_counter)
+}
+
+fn card_strength_with_jokers(card: &Card) -> Card {
+    match card {
+        Card::J => Card::Joker,
+        _ => *card,
     }
 }
 
+fn card_stats_with_jokers(hand: &Hand) -> (usize, usize) {
+    let j_counter = hand.card_counters[J_INDEX];
+    let card_counters_no_j = &hand.card_counters[..J_INDEX];
+
+    let num_counters = card_counters_no_j
+        .iter()
+        .filter(|&counter| *counter != 0)
+        .count();
+    let max_counter = j_counter + card_counters_no_j.iter().copied().max().unwrap_or_default();
+
+    (num_counters, max_counter)
+}
+
 const J_INDEX: usize = 12;
 
 impl<'a> Puzzle<'a> for Day {
-    type Sol1Type = u32;
+    const YEAR: u32 = 2023;
+    const DAY: u8 = 7;
+    const GENERATOR: Option<Generator> = Some(generate);
+
+    // Winnings grow with the square of the number of hands
+    type Sol1Type = Result<u64>;
     type Sol2Type = Self::Sol1Type;
 
     fn parse(input: &'a str) -> Result<Self> {
@@ -99,11 +145,15 @@ impl<'a> Puzzle<'a> for Day {
             .map(|hand| {
                 let (cards_str, bid) = hand
                     .split_once(' ')
-                    .with_context(|| format!("Failed to split hand: `{hand}`"))?;
-                let bid = bid.parse()?;
+                    .with_span(hand, || "Failed to split hand")?;
+                if cards_str.chars().count() != 5 {
+                    let message = "A hand needs to consist of exactly five cards";
+                    return Err(ParseError::new(cards_str, message).into());
+                }
+                let bid = bid.parse().with_span(bid, || "Failed to parse bid")?;
                 let mut card_counters = Box::new([0; 13]);
                 let mut cards = Box::new([Card::A; 5]);
-                for (card, c) in zip(cards.iter_mut(), cards_str.chars()) {
+                for (card, (offset, c)) in zip(cards.iter_mut(), cards_str.char_indices()) {
                     let i;
                     (i, *card) = match c {
                         'A' => (0, Card::A),
@@ -119,7 +169,11 @@ impl<'a> Puzzle<'a> for Day {
                         '3' => (10, Card::Three),
                         '2' => (11, Card::Two),
                         'J' => (J_INDEX, Card::J),
-                        _ => bail!("Unrecognized card `{c}`"),
+                        _ => {
+                            let span = &cards_str[offset..offset + c.len_utf8()];
+                            let message = format!("Unrecognized card `{c}`");
+                            return Err(ParseError::new(span, message).into());
+                        }
                     };
 
                     card_counters[i] += 1;
@@ -137,57 +191,105 @@ impl<'a> Puzzle<'a> for Day {
     }
 
     fn solve_problem_1(&self) -> Self::Sol1Type {
-        let card_strength = |card: &Card| *card;
-
-        let card_stats = |hand: &Hand| {
-            let num_counters = hand
-                .card_counters
-                .iter()
-                .filter(|&counter| *counter != 0)
-                .count();
-            let max_counter = hand.card_counters.iter().copied().max().unwrap_or_default();
-
-            (num_counters, max_counter)
-        };
-
-        self.winnings(card_strength, card_stats)
+        winnings(&mut self.typed_hands(card_stats), card_strength)
     }
 
     fn solve_problem_2(&self) -> Self::Sol2Type {
-        let card_strength = |card: &Card| match card {
-            Card::J => Card::Joker,
-            _ => *card,
-        };
+        winnings(
+            &mut self.typed_hands(card_stats_with_jokers),
+            card_strength_with_jokers,
+        )
+    }
 
-        let card_stats = |hand: &Hand| {
-            let j_counter = hand.card_counters[J_INDEX];
-            let card_counters_no_j = &hand.card_counters[..J_INDEX];
+    fn solve_both(&self) -> Option<(Self::Sol1Type, Self::Sol2Type)> {
+        let mut hands = self.typed_hands(card_stats);
+        let winnings_1 = winnings(&mut hands, card_strength);
 
-            let num_counters = card_counters_no_j
-                .iter()
-                .filter(|&counter| *counter != 0)
-                .count();
-            let max_counter =
-                j_counter + card_counters_no_j.iter().copied().max().unwrap_or_default();
+        // Reuse the list for part 2. Jokers only ever improve a hand, so it's already mostly in
+        // the right order, which makes the second sort cheaper.
+        for (hand, type_) in hands.iter_mut() {
+            *type_ = HandType::from_stats(card_stats_with_jokers(hand));
+        }
+        let winnings_2 = winnings(&mut hands, card_strength_with_jokers);
 
-            (num_counters, max_counter)
-        };
+        Some((winnings_1, winnings_2))
+    }
+}
 
-        self.winnings(card_strength, card_stats)
+/// Generates `size` distinct hands with bids.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const CARDS: &[u8] = b"AKQJT98765432";
+    // Each hand has to be distinct, so the order of hands is well-defined
+    let size = size.min(CARDS.len().pow(5));
+
+    let mut hands = HashSet::new();
+    let mut input = String::new();
+    while hands.len() < size {
+        let hand: Vec<_> = (0..5).map(|_| *rng.pick(CARDS)).collect();
+        let hand = String::from_utf8(hand).unwrap();
+        if hands.insert(hand.clone()) {
+            writeln!(input, "{hand} {}", rng.range(1..1000)).unwrap();
+        }
     }
+    input
 }
 
 #[cfg(test)]
 mod tests {
     use crate::Puzzle;
     use indoc::indoc;
+    use itertools::Itertools;
+    use proptest::prelude::*;
 
-    #[test]
-    fn test_example() {
-        let input = std::fs::read_to_string("input/2023/07_example_1.txt").unwrap();
-        let day = super::Day::parse(&input).unwrap();
-        assert_eq!(day.solve_problem_1(), 6440);
-        assert_eq!(day.solve_problem_2(), 5905);
+    const STRENGTHS: &str = "23456789TJQKA";
+    const STRENGTHS_WITH_JOKERS: &str = "J23456789TQKA";
+
+    /// Ranks a hand type by the sorted card counts, e.g. `[3, 2]` for a full house.
+    fn reference_type(cards: &str) -> Vec<usize> {
+        let counts = cards.chars().counts();
+        counts.into_values().sorted().rev().collect()
+    }
+
+    /// Tries every combination of cards the jokers could stand for.
+    fn reference_type_with_jokers(cards: &str) -> Vec<usize> {
+        let others: String = cards.chars().filter(|&c| c != 'J').collect();
+        let jokers = cards.len() - others.len();
+        STRENGTHS_WITH_JOKERS[1..]
+            .chars()
+            .combinations_with_replacement(jokers)
+            .map(|substitutes| {
+                let substitutes: String = substitutes.into_iter().collect();
+                reference_type(&format!("{others}{substitutes}"))
+            })
+            .max()
+            .unwrap()
+    }
+
+    fn reference(
+        hands: &[(String, u64)],
+        hand_type: fn(&str) -> Vec<usize>,
+        strengths: &str,
+    ) -> u64 {
+        let strength = |cards: &str| -> Vec<_> {
+            cards.chars().map(|c| strengths.find(c).unwrap()).collect()
+        };
+        hands
+            .iter()
+            .sorted_by_cached_key(|(cards, _)| (hand_type(cards), strength(cards)))
+            .zip(1..)
+            .map(|((_, bid), rank)| bid * rank)
+            .sum()
+    }
+
+    /// Distinct hands, since the order of equal hands would be ambiguous. Some hands are drawn
+    /// from fewer kinds of cards, so full houses and multiple jokers are common.
+    fn hands() -> impl Strategy<Value = Vec<(String, u64)>> {
+        let kinds = prop::sample::select(vec![STRENGTHS, "KQJ2", "JA"]);
+        let hand = kinds
+            .prop_flat_map(|kinds| prop::collection::vec(prop::sample::select(kinds.as_bytes()), 5))
+            .prop_map(|cards| String::from_utf8(cards).unwrap());
+        prop::collection::btree_map(hand, 1..1000u64, 1..20)
+            .prop_map(|hands| hands.into_iter().collect())
     }
 
     #[test]
@@ -202,6 +304,28 @@ mod tests {
         "};
 
         let day = super::Day::parse(INPUT).unwrap();
-        assert_eq!(day.solve_problem_2(), 1101);
+        assert_eq!(day.solve_problem_2().unwrap(), 1101);
+    }
+
+    #[test]
+    fn test_short_hand() {
+        assert!(super::Day::parse("32T3K 765\nKTT 220\n").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_against_reference(hands in hands()) {
+            let input: String = hands
+                .iter()
+                .map(|(cards, bid)| format!("{cards} {bid}\n"))
+                .collect();
+            let day = super::Day::parse(&input).unwrap();
+
+            prop_assert_eq!(day.solve_problem_1().unwrap(), reference(&hands, reference_type, STRENGTHS));
+            prop_assert_eq!(
+                day.solve_problem_2().unwrap(),
+                reference(&hands, reference_type_with_jokers, STRENGTHS_WITH_JOKERS)
+            );
+        }
     }
 }
@@ -1,7 +1,9 @@
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
+crate::registry::puzzles! {
+    day01::Day,
+    day02::Day,
+    day03::Day,
+    day04::Day,
+    day05::Day,
+    day06::Day,
+    day07::Day,
+}